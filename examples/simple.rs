@@ -34,9 +34,10 @@ fn toggle_log(mut commands: Commands, keyboard: Res<ButtonInput<KeyCode>>) {
     } else if keyboard.just_pressed(KeyCode::Digit1) {
         trace!("trace log");
     } else if keyboard.just_pressed(KeyCode::Digit2) {
+        let _span = info_span!("example", key = 2).entered();
         debug!("debug log");
     } else if keyboard.just_pressed(KeyCode::Digit3) {
-        info!("info log");
+        info!(answer = 42, "info log");
     } else if keyboard.just_pressed(KeyCode::Digit4) {
         warn!("warn log");
     } else if keyboard.just_pressed(KeyCode::Digit5) {
@@ -0,0 +1,80 @@
+//! Copying logs to the system clipboard, e.g. to paste them into a bug report.
+
+use crate::{
+    export::{show_status, text_line},
+    log_list::FilteredLogs,
+    log_viewer::{CopyButton, DetailCopyButton, LogViewerState, StatusTextMarker},
+    theme::{ThemeColor, ThemedText},
+    LogStore,
+};
+use bevy_clipboard::Clipboard;
+use bevy_ecs::prelude::*;
+use bevy_ui::prelude::*;
+
+/// Copies the selected logs, or all logs passing the filters if none are selected, and clears
+/// the selection.
+pub(crate) fn on_copy_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<CopyButton>)>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+    log_store: Res<LogStore>,
+    filtered: Res<FilteredLogs>,
+    mut clipboard: ResMut<Clipboard>,
+    mut status_query: Query<(&mut Text, &mut ThemedText, &mut Node), With<StatusTextMarker>>,
+) {
+    for interaction in interaction_query.iter() {
+        if !matches!(*interaction, Interaction::Pressed) {
+            continue;
+        }
+        let lines: Vec<String> = if log_viewer_res.selected.is_empty() {
+            filtered
+                .ids()
+                .filter_map(|id| log_store.get(id))
+                .map(text_line)
+                .collect()
+        } else {
+            log_viewer_res
+                .selected
+                .iter()
+                .filter_map(|id| log_store.get(*id))
+                .map(text_line)
+                .collect()
+        };
+        copy_lines(&mut clipboard, &lines, &mut status_query);
+        log_viewer_res.selected.clear();
+    }
+}
+
+/// Copies the log shown in the detail panel.
+pub(crate) fn on_detail_copy_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<DetailCopyButton>)>,
+    log_viewer_res: Res<LogViewerState>,
+    log_store: Res<LogStore>,
+    mut clipboard: ResMut<Clipboard>,
+    mut status_query: Query<(&mut Text, &mut ThemedText, &mut Node), With<StatusTextMarker>>,
+) {
+    for interaction in interaction_query.iter() {
+        if !matches!(*interaction, Interaction::Pressed) {
+            continue;
+        }
+        let lines: Vec<String> = log_viewer_res
+            .detail_id
+            .and_then(|id| log_store.get(id))
+            .map(text_line)
+            .into_iter()
+            .collect();
+        copy_lines(&mut clipboard, &lines, &mut status_query);
+    }
+}
+
+fn copy_lines(
+    clipboard: &mut Clipboard,
+    lines: &[String],
+    status_query: &mut Query<(&mut Text, &mut ThemedText, &mut Node), With<StatusTextMarker>>,
+) {
+    let (status, color) = match clipboard.set_text(lines.join("\n")) {
+        Ok(()) if lines.len() == 1 => ("Copied 1 line".to_string(), ThemeColor::Text),
+        Ok(()) => (format!("Copied {} lines", lines.len()), ThemeColor::Text),
+        Err(error) => (format!("Copy failed: {error}"), ThemeColor::Error),
+    };
+    show_status(status_query, &status, color);
+}
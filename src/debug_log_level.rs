@@ -1,4 +1,3 @@
-use bevy_color::{palettes::css, prelude::*};
 use bevy_ecs::prelude::*;
 use bevy_log::{tracing::level_filters::LevelFilter, Level};
 use core::fmt;
@@ -37,25 +36,6 @@ impl From<DebugLogLevel> for Level {
     }
 }
 
-impl From<DebugLogLevel> for Srgba {
-    fn from(log_level: DebugLogLevel) -> Self {
-        match log_level.0 {
-            Level::TRACE => css::MEDIUM_ORCHID,
-            Level::DEBUG => css::DEEP_SKY_BLUE,
-            Level::INFO => css::LIME,
-            Level::WARN => css::YELLOW,
-            Level::ERROR => css::RED,
-        }
-    }
-}
-
-impl From<DebugLogLevel> for Color {
-    fn from(log_level: DebugLogLevel) -> Self {
-        let color: Srgba = log_level.into();
-        color.into()
-    }
-}
-
 impl From<DebugLogLevel> for String {
     fn from(log_level: DebugLogLevel) -> Self {
         log_level.0.to_string()
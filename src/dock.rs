@@ -0,0 +1,339 @@
+//! Where the viewer is placed on screen, and moving and resizing it by dragging.
+
+use crate::{
+    log_viewer::{LogViewerMarker, LogViewerState, ResizeGripMarker, ResizeHandleMarker},
+    ScreenCorner,
+};
+use bevy_camera::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use bevy_picking::prelude::*;
+use bevy_ui::prelude::*;
+use bevy_utils::prelude::*;
+
+/// Default share of the screen in percent the viewer covers while small.
+pub(crate) const DEFAULT_SMALL_SIZE: f32 = 40.;
+
+/// Default share of the screen in percent the viewer covers while big.
+pub(crate) const DEFAULT_BIG_SIZE: f32 = 100.;
+
+/// Smallest share of the screen in percent the viewer can be resized to.
+pub(crate) const MIN_SIZE: f32 = 10.;
+
+/// Thickness in logical px of the resize handle on the viewer's inner edge.
+const HANDLE_THICKNESS: f32 = 8.;
+
+/// Default position and size in logical px of the floating viewer.
+pub(crate) const DEFAULT_FLOATING_RECT: Rect = Rect {
+    min: Vec2::new(16., 16.),
+    max: Vec2::new(496., 316.),
+};
+
+/// Smallest size in logical px the floating viewer can be resized to.
+const MIN_FLOATING_SIZE: Vec2 = Vec2::new(160., 80.);
+
+/// Size in logical px of the squares in the floating viewer's corners that resize it.
+const CORNER_SIZE: f32 = 12.;
+
+/// Edge of the screen the viewer is docked to, set via
+/// [`LogViewerPlugin::dock`](crate::LogViewerPlugin::dock).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogViewerDock {
+    /// Spans the width of the screen at its top.
+    #[default]
+    Top,
+    /// Spans the width of the screen at its bottom.
+    Bottom,
+    /// Spans the height of the screen on its left.
+    Left,
+    /// Spans the height of the screen on its right.
+    Right,
+    /// A box floating over the game that can be moved and resized, see
+    /// [`LogViewerSize::Floating`](crate::LogViewerSize::Floating).
+    Floating,
+}
+
+/// Square in a corner of the floating viewer that resizes it when dragged.
+#[derive(Component)]
+pub(crate) struct ResizeCorner(pub(crate) ScreenCorner);
+
+/// Places the viewer according to its dock and size, and its resize handle on the inner edge.
+/// Keeps the floating viewer on screen when the window shrinks.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_viewer_layout(
+    mut log_viewer_res: ResMut<LogViewerState>,
+    camera_query: Query<&Camera, With<LogViewerMarker>>,
+    mut viewer_query: Query<
+        &mut Node,
+        (
+            With<LogViewerMarker>,
+            Without<ResizeHandleMarker>,
+            Without<ResizeGripMarker>,
+        ),
+    >,
+    mut handle_query: Query<
+        &mut Node,
+        (
+            With<ResizeHandleMarker>,
+            Without<LogViewerMarker>,
+            Without<ResizeGripMarker>,
+        ),
+    >,
+    mut grip_query: Query<
+        &mut Node,
+        (
+            With<ResizeGripMarker>,
+            Without<LogViewerMarker>,
+            Without<ResizeHandleMarker>,
+        ),
+    >,
+) {
+    if let Some(screen) = camera_query.iter().find_map(Camera::logical_viewport_size) {
+        let rect = clamp_to_screen(log_viewer_res.floating_rect, screen);
+        if rect != log_viewer_res.floating_rect {
+            log_viewer_res.floating_rect = rect;
+        }
+    }
+    if !log_viewer_res.is_changed() {
+        return;
+    }
+    let dock = log_viewer_res.dock;
+    let floating = log_viewer_res.floating && !log_viewer_res.fullscreen;
+    let size = if log_viewer_res.fullscreen {
+        log_viewer_res.big_size
+    } else {
+        log_viewer_res.size
+    };
+
+    for mut node in viewer_query.iter_mut() {
+        let mut layout = node.clone();
+        layout.top = Val::Auto;
+        layout.bottom = Val::Auto;
+        layout.left = Val::Auto;
+        layout.right = Val::Auto;
+        layout.width = Val::Percent(100.);
+        layout.height = Val::Percent(100.);
+        match dock {
+            _ if floating => {
+                let Rect { min, max } = log_viewer_res.floating_rect;
+                layout.left = Val::Px(min.x);
+                layout.top = Val::Px(min.y);
+                layout.width = Val::Px(max.x - min.x);
+                layout.height = Val::Px(max.y - min.y);
+                layout.border = UiRect::all(Val::Px(1.));
+            }
+            LogViewerDock::Top => {
+                layout.top = Val::Px(0.);
+                layout.height = Val::Percent(size);
+                layout.border = UiRect::bottom(Val::Px(1.));
+            }
+            LogViewerDock::Bottom => {
+                layout.bottom = Val::Px(0.);
+                layout.height = Val::Percent(size);
+                layout.border = UiRect::top(Val::Px(1.));
+            }
+            LogViewerDock::Left => {
+                layout.left = Val::Px(0.);
+                layout.width = Val::Percent(size);
+                layout.border = UiRect::right(Val::Px(1.));
+            }
+            LogViewerDock::Right => {
+                layout.right = Val::Px(0.);
+                layout.width = Val::Percent(size);
+                layout.border = UiRect::left(Val::Px(1.));
+            }
+            // Fullscreen, covering the whole screen.
+            LogViewerDock::Floating => {
+                layout.top = Val::Px(0.);
+                layout.left = Val::Px(0.);
+                layout.border = UiRect::all(Val::Px(1.));
+            }
+        }
+        node.set_if_neq(layout);
+    }
+
+    // Nothing to resize while big, floating or filling its own window.
+    let resizable = !log_viewer_res.fullscreen && !floating && !log_viewer_res.own_window;
+    let horizontal = matches!(dock, LogViewerDock::Top | LogViewerDock::Bottom);
+    for mut node in handle_query.iter_mut() {
+        let mut layout = Node {
+            display: if resizable {
+                Display::Flex
+            } else {
+                Display::None
+            },
+            position_type: PositionType::Absolute,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        };
+        if horizontal {
+            layout.left = Val::Px(0.);
+            layout.right = Val::Px(0.);
+            layout.height = Val::Px(HANDLE_THICKNESS);
+        } else {
+            layout.top = Val::Px(0.);
+            layout.bottom = Val::Px(0.);
+            layout.width = Val::Px(HANDLE_THICKNESS);
+        }
+        match dock {
+            LogViewerDock::Top => layout.bottom = Val::Px(0.),
+            LogViewerDock::Bottom => layout.top = Val::Px(0.),
+            LogViewerDock::Left => layout.right = Val::Px(0.),
+            LogViewerDock::Right | LogViewerDock::Floating => layout.left = Val::Px(0.),
+        }
+        node.set_if_neq(layout);
+    }
+    let (width, height) = if horizontal { (40., 3.) } else { (3., 40.) };
+    for mut grip in grip_query.iter_mut() {
+        let mut layout = grip.clone();
+        layout.width = Val::Px(width);
+        layout.height = Val::Px(height);
+        grip.set_if_neq(layout);
+    }
+}
+
+/// Resizes the docked viewer by dragging its resize handle.
+pub(crate) fn on_resize_handle_drag(
+    drag: On<Pointer<Drag>>,
+    camera_query: Query<&Camera, With<LogViewerMarker>>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+) {
+    if log_viewer_res.own_window {
+        return;
+    }
+    let Some(screen) = camera_query.iter().find_map(Camera::logical_viewport_size) else {
+        return;
+    };
+    // Share of the screen in percent the handle moved towards the screen's center.
+    let grown = match log_viewer_res.dock {
+        LogViewerDock::Top => drag.delta.y / screen.y,
+        LogViewerDock::Bottom => -drag.delta.y / screen.y,
+        LogViewerDock::Left => drag.delta.x / screen.x,
+        LogViewerDock::Right => -drag.delta.x / screen.x,
+        LogViewerDock::Floating => return,
+    } * 100.;
+    // Growing past the big size would make going fullscreen shrink the viewer.
+    let max = log_viewer_res.big_size.max(MIN_SIZE);
+    log_viewer_res.size = (log_viewer_res.size + grown).clamp(MIN_SIZE, max);
+}
+
+/// Shows the resize corners while the viewer floats.
+pub(crate) fn sync_resize_corners(
+    log_viewer_res: Res<LogViewerState>,
+    mut corner_query: Query<(&mut Node, &ResizeCorner)>,
+) {
+    if !log_viewer_res.is_changed() {
+        return;
+    }
+    let floating =
+        log_viewer_res.floating && !log_viewer_res.fullscreen && !log_viewer_res.own_window;
+
+    for (mut node, ResizeCorner(corner)) in corner_query.iter_mut() {
+        let mut layout = Node {
+            display: if floating {
+                Display::Flex
+            } else {
+                Display::None
+            },
+            position_type: PositionType::Absolute,
+            width: Val::Px(CORNER_SIZE),
+            height: Val::Px(CORNER_SIZE),
+            ..default()
+        };
+        match corner {
+            ScreenCorner::TopLeft | ScreenCorner::TopRight => layout.top = Val::Px(0.),
+            ScreenCorner::BottomLeft | ScreenCorner::BottomRight => layout.bottom = Val::Px(0.),
+        }
+        match corner {
+            ScreenCorner::TopLeft | ScreenCorner::BottomLeft => layout.left = Val::Px(0.),
+            ScreenCorner::TopRight | ScreenCorner::BottomRight => layout.right = Val::Px(0.),
+        }
+        node.set_if_neq(layout);
+    }
+}
+
+/// Moves the floating viewer by dragging the empty space and labels of its title bar, keeping
+/// it on screen. Dragging its chips, buttons or search box doesn't move it.
+pub(crate) fn on_title_bar_drag(
+    drag: On<Pointer<Drag>>,
+    parent_query: Query<&ChildOf>,
+    control_query: Query<(), With<Interaction>>,
+    camera_query: Query<&Camera, With<LogViewerMarker>>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+) {
+    if !log_viewer_res.floating || log_viewer_res.fullscreen {
+        return;
+    }
+    let title_bar = drag.event().entity;
+    let target = drag.original_event_target();
+    let on_control = std::iter::once(target)
+        .chain(parent_query.iter_ancestors(target))
+        .take_while(|entity| *entity != title_bar)
+        .any(|entity| control_query.contains(entity));
+    if on_control {
+        return;
+    }
+    let Some(screen) = camera_query.iter().find_map(Camera::logical_viewport_size) else {
+        return;
+    };
+
+    let rect = log_viewer_res.floating_rect;
+    log_viewer_res.floating_rect = clamp_to_screen(
+        Rect::from_corners(rect.min + drag.delta, rect.max + drag.delta),
+        screen,
+    );
+}
+
+/// Moves the floating viewer's rect on screen, and shrinks it if it doesn't fit.
+fn clamp_to_screen(rect: Rect, screen: Vec2) -> Rect {
+    let size = rect.size().min(screen).max(MIN_FLOATING_SIZE);
+    let min = rect.min.clamp(Vec2::ZERO, (screen - size).max(Vec2::ZERO));
+    if min == rect.min && size == rect.size() {
+        rect
+    } else {
+        Rect::from_corners(min, min + size)
+    }
+}
+
+/// Resizes the floating viewer by dragging one of its corners, keeping it on screen.
+pub(crate) fn on_resize_corner_drag(
+    drag: On<Pointer<Drag>>,
+    corner_query: Query<&ResizeCorner>,
+    camera_query: Query<&Camera, With<LogViewerMarker>>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+) {
+    if !log_viewer_res.floating || log_viewer_res.fullscreen || log_viewer_res.own_window {
+        return;
+    }
+    let Ok(ResizeCorner(corner)) = corner_query.get(drag.event().entity) else {
+        return;
+    };
+    let Some(screen) = camera_query.iter().find_map(Camera::logical_viewport_size) else {
+        return;
+    };
+
+    let Rect { mut min, mut max } = log_viewer_res.floating_rect;
+    let delta = drag.delta;
+    match corner {
+        ScreenCorner::TopLeft | ScreenCorner::BottomLeft => {
+            min.x = (min.x + delta.x).min(max.x - MIN_FLOATING_SIZE.x).max(0.);
+        }
+        ScreenCorner::TopRight | ScreenCorner::BottomRight => {
+            max.x = (max.x + delta.x)
+                .min(screen.x)
+                .max(min.x + MIN_FLOATING_SIZE.x);
+        }
+    }
+    match corner {
+        ScreenCorner::TopLeft | ScreenCorner::TopRight => {
+            min.y = (min.y + delta.y).min(max.y - MIN_FLOATING_SIZE.y).max(0.);
+        }
+        ScreenCorner::BottomLeft | ScreenCorner::BottomRight => {
+            max.y = (max.y + delta.y)
+                .min(screen.y)
+                .max(min.y + MIN_FLOATING_SIZE.y);
+        }
+    }
+    log_viewer_res.floating_rect = Rect { min, max };
+}
@@ -0,0 +1,227 @@
+//! Writing the captured logs to a file, for devices where the viewer is the only place they exist.
+
+use crate::{
+    log_list::format_timestamp,
+    log_viewer::{ExportButton, LogViewerState, StatusTextMarker},
+    theme::{ThemeColor, ThemedText},
+    FieldValue, LogEvent, LogStore,
+};
+use bevy_ecs::prelude::*;
+use bevy_log::{info, warn};
+use bevy_time::prelude::*;
+use bevy_ui::prelude::*;
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// Seconds the status text stays in the title bar.
+const STATUS_SECONDS: f32 = 5.;
+
+/// File format of [`ExportLogs`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One line per log, formatted like in the viewer.
+    #[default]
+    Text,
+    /// One JSON object per line with `timestamp`, `level`, `target`, `message`, `fields` and
+    /// `spans`.
+    JsonLines,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Text => "log",
+            ExportFormat::JsonLines => "jsonl",
+        }
+    }
+}
+
+/// Writes all logs currently held by the [`LogStore`] to `path`, regardless of the viewer's
+/// filters. An existing file is overwritten.
+#[derive(Event, Debug, Clone)]
+pub struct ExportLogs {
+    pub path: PathBuf,
+    pub format: ExportFormat,
+}
+
+impl ExportLogs {
+    pub fn new(path: impl Into<PathBuf>, format: ExportFormat) -> Self {
+        Self {
+            path: path.into(),
+            format,
+        }
+    }
+}
+
+pub(crate) fn handle_export_logs(
+    trigger: On<ExportLogs>,
+    log_store: Res<LogStore>,
+    mut status_query: Query<(&mut Text, &mut ThemedText, &mut Node), With<StatusTextMarker>>,
+) {
+    let ExportLogs { path, format } = trigger.event();
+
+    let (status, color) = match export(&log_store, path, *format) {
+        Ok(()) => {
+            info!(
+                "exported {} log lines to {}",
+                log_store.len(),
+                path.display()
+            );
+            (format!("Exported to {}", path.display()), ThemeColor::Text)
+        }
+        Err(error) => {
+            warn!("failed to export logs to {}: {error}", path.display());
+            (format!("Export failed: {error}"), ThemeColor::Error)
+        }
+    };
+
+    show_status(&mut status_query, &status, color);
+}
+
+/// Shows the outcome of an export or copy in the title bar.
+pub(crate) fn show_status(
+    status_query: &mut Query<(&mut Text, &mut ThemedText, &mut Node), With<StatusTextMarker>>,
+    status: &str,
+    color: ThemeColor,
+) {
+    for (mut text, mut text_color, mut node) in status_query.iter_mut() {
+        text.0 = status.to_string();
+        text_color.set_if_neq(ThemedText(color));
+        node.display = Display::Flex;
+    }
+}
+
+/// Hides the status text once it was shown unchanged for a while.
+pub(crate) fn hide_status(
+    time: Res<Time>,
+    mut shown_for: Local<f32>,
+    mut status_query: Query<(Ref<Text>, &mut Node), With<StatusTextMarker>>,
+) {
+    for (text, mut node) in status_query.iter_mut() {
+        if text.is_changed() {
+            *shown_for = 0.;
+        } else if node.display != Display::None {
+            *shown_for += time.delta_secs();
+            if *shown_for >= STATUS_SECONDS {
+                node.display = Display::None;
+            }
+        }
+    }
+}
+
+fn export(log_store: &LogStore, path: &Path, format: ExportFormat) -> io::Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+    for (_, record) in log_store.iter() {
+        match format {
+            ExportFormat::Text => writeln!(writer, "{}", text_line(record))?,
+            ExportFormat::JsonLines => writeln!(writer, "{}", json_line(record))?,
+        }
+    }
+    writer.flush()
+}
+
+pub(crate) fn text_line(record: &LogEvent) -> String {
+    let mut line = format!("{} {} ", format_timestamp(&record.timestamp), record.level);
+    if !record.spans.is_empty() {
+        line.push_str(&record.spans_text());
+        line.push(' ');
+    }
+    line.push_str(&record.target);
+    line.push(':');
+    if let Some(message) = &record.message {
+        line.push(' ');
+        line.push_str(message);
+    }
+    if !record.fields.is_empty() {
+        line.push(' ');
+        line.push_str(&record.fields_text());
+    }
+    line
+}
+
+pub(crate) fn json_line(record: &LogEvent) -> serde_json::Value {
+    let fields = |fields: &[(Cow<'static, str>, FieldValue)]| {
+        fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_json()))
+            .collect::<serde_json::Map<_, _>>()
+    };
+    // In full precision, unlike the viewer.
+    let timestamp = record
+        .timestamp
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| format_timestamp(&record.timestamp));
+
+    serde_json::json!({
+        "timestamp": timestamp,
+        "level": record.level.as_str(),
+        "target": record.target,
+        "module_path": record.module_path,
+        "file": record.file,
+        "line": record.line,
+        "message": record.message,
+        "fields": fields(&record.fields),
+        "spans": record
+            .spans
+            .iter()
+            .map(|span| serde_json::json!({ "name": span.name, "fields": fields(&span.fields) }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Directory the export button writes to unless set via
+/// [`LogViewerPlugin::export_dir`](crate::LogViewerPlugin::export_dir).
+fn default_export_dir() -> io::Result<PathBuf> {
+    if cfg!(target_arch = "wasm32") {
+        // `temp_dir` panics on platforms without a filesystem.
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no filesystem on this platform",
+        ))
+    } else {
+        Ok(std::env::temp_dir())
+    }
+}
+
+pub(crate) fn on_export_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ExportButton>)>,
+    log_viewer_res: Res<LogViewerState>,
+    mut status_query: Query<(&mut Text, &mut ThemedText, &mut Node), With<StatusTextMarker>>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if !matches!(*interaction, Interaction::Pressed) {
+            continue;
+        }
+        let dir = match &log_viewer_res.export_dir {
+            Some(dir) => dir.clone(),
+            None => match default_export_dir() {
+                Ok(dir) => dir,
+                Err(error) => {
+                    let status = format!("Export failed: {error}");
+                    show_status(&mut status_query, &status, ThemeColor::Error);
+                    continue;
+                }
+            },
+        };
+        let format = ExportFormat::Text;
+        // In milliseconds, so exports in quick succession don't overwrite each other.
+        let file_name = format!(
+            "bevy_debug_log_{}.{}",
+            OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000,
+            format.extension()
+        );
+        commands.trigger(ExportLogs::new(dir.join(file_name), format));
+    }
+}
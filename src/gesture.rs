@@ -0,0 +1,141 @@
+//! Touch gestures opening the viewer, so mobile builds have a way in without a keyboard.
+
+use crate::{log_viewer::LogViewerMarker, LogViewerVisibility};
+use bevy_camera::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_input::touch::Touches;
+use bevy_math::prelude::*;
+use bevy_time::prelude::*;
+use std::collections::HashSet;
+
+/// Size in logical px of the square in a screen corner that starts a
+/// [`ToggleGesture::CornerLongPress`].
+const CORNER_SIZE: f32 = 64.;
+
+/// How far in logical px a finger may move during a gesture.
+const TOUCH_SLOP: f32 = 16.;
+
+/// Seconds within which all fingers of a [`ToggleGesture::ThreeFingerTap`] have to land.
+const TAP_LAND_SECONDS: f32 = 0.2;
+
+/// Seconds from the first finger landing within which all fingers of a
+/// [`ToggleGesture::ThreeFingerTap`] have to lift.
+const TAP_SECONDS: f32 = 0.5;
+
+/// A corner of the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Touch gesture triggering [`LogViewerVisibility::Toggle`], set via
+/// [`LogViewerPlugin::toggle_gesture`](crate::LogViewerPlugin::toggle_gesture) or by inserting
+/// the resource. No gesture is detected without it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub enum ToggleGesture {
+    /// Three fingers landing at once and lifting again shortly after, without moving.
+    ThreeFingerTap,
+    /// A finger held still in a corner of the screen for `seconds`.
+    CornerLongPress { corner: ScreenCorner, seconds: f32 },
+}
+
+/// Touches of the gesture in progress.
+#[derive(Default)]
+pub(crate) struct GestureState {
+    /// Ids of the fingers that landed since the screen was last untouched, and when.
+    tap_started: Vec<(u64, f32)>,
+    /// Whether a finger of the tap moved or was cancelled, so it isn't one.
+    tap_failed: bool,
+    long_press_fired: HashSet<u64>,
+    long_press_started: Vec<(u64, f32)>,
+}
+
+pub(crate) fn detect_toggle_gesture(
+    gesture: Option<Res<ToggleGesture>>,
+    touches: Option<Res<Touches>>,
+    time: Option<Res<Time>>,
+    camera_query: Query<&Camera, With<LogViewerMarker>>,
+    mut state: Local<GestureState>,
+    mut commands: Commands,
+) {
+    let (Some(gesture), Some(touches), Some(time)) = (gesture, touches, time) else {
+        return;
+    };
+    let now = time.elapsed_secs();
+
+    match *gesture {
+        ToggleGesture::ThreeFingerTap => {
+            for touch in touches.iter_just_pressed() {
+                state.tap_started.push((touch.id(), now));
+            }
+            let moved = touches
+                .iter()
+                .chain(touches.iter_just_released())
+                .any(|touch| touch.position().distance(touch.start_position()) > TOUCH_SLOP);
+            if moved || touches.iter_just_canceled().next().is_some() {
+                state.tap_failed = true;
+            }
+
+            // Decide once the last finger lifted.
+            if touches.iter().next().is_some() || state.tap_started.is_empty() {
+                return;
+            }
+            let started = std::mem::take(&mut state.tap_started);
+            let failed = std::mem::take(&mut state.tap_failed);
+            let first = started.iter().map(|(_, at)| *at).fold(f32::MAX, f32::min);
+            let last = started.iter().map(|(_, at)| *at).fold(f32::MIN, f32::max);
+            if !failed
+                && started.len() == 3
+                && last - first <= TAP_LAND_SECONDS
+                && now - first <= TAP_SECONDS
+            {
+                commands.trigger(LogViewerVisibility::Toggle);
+            }
+        }
+        ToggleGesture::CornerLongPress { corner, seconds } => {
+            let Some(size) = camera_query.iter().find_map(Camera::logical_viewport_size) else {
+                return;
+            };
+            let GestureState {
+                long_press_fired,
+                long_press_started,
+                ..
+            } = &mut *state;
+
+            for touch in touches.iter_just_pressed() {
+                if in_corner(touch.start_position(), corner, size) {
+                    long_press_started.push((touch.id(), now));
+                }
+            }
+            // Forget touches that ended or wandered off.
+            long_press_started.retain(|(id, _)| {
+                touches.get_pressed(*id).is_some_and(|touch| {
+                    touch.position().distance(touch.start_position()) <= TOUCH_SLOP
+                })
+            });
+            long_press_fired.retain(|id| touches.get_pressed(*id).is_some());
+
+            for (id, started) in long_press_started.iter() {
+                if now - started >= seconds && long_press_fired.insert(*id) {
+                    commands.trigger(LogViewerVisibility::Toggle);
+                }
+            }
+        }
+    }
+}
+
+fn in_corner(position: Vec2, corner: ScreenCorner, size: Vec2) -> bool {
+    let left = position.x <= CORNER_SIZE;
+    let right = position.x >= size.x - CORNER_SIZE;
+    let top = position.y <= CORNER_SIZE;
+    let bottom = position.y >= size.y - CORNER_SIZE;
+    match corner {
+        ScreenCorner::TopLeft => top && left,
+        ScreenCorner::TopRight => top && right,
+        ScreenCorner::BottomLeft => bottom && left,
+        ScreenCorner::BottomRight => bottom && right,
+    }
+}
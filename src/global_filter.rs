@@ -0,0 +1,152 @@
+//! The global tracing filter that can be changed while the app is running.
+//!
+//! Unlike the viewer's own filters, which only hide lines that were already captured, this filter
+//! decides what gets logged at all, for the terminal and any other layer as much as the viewer.
+
+use crate::{
+    log_viewer::{LogFilterErrorMarker, LogFilterInputMarker, LogFilterRowMarker},
+    utils::TextInput,
+    LogCaptureLayer,
+};
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_log::{
+    tracing_subscriber::{registry::Registry, reload, EnvFilter, Layer},
+    warn, BoxedLayer,
+};
+use bevy_ui::prelude::*;
+
+/// Directives of the runtime filter before it is first set, letting everything through to
+/// `LogPlugin`'s own filter.
+const INITIAL_DIRECTIVES: &str = "trace";
+
+/// Like [`log_capture_layer`](crate::log_capture_layer), but additionally installs a global tracing filter that can be
+/// changed at runtime via [`SetLogFilter`] or the log filter field of the viewer's target panel.
+///
+/// `LogPlugin`'s own `filter` and `level` still apply before the runtime filter, so they have to
+/// let through everything the runtime filter may want to enable, e.g. `filter: "trace".into()`
+/// and `level: Level::TRACE`. Use [`LogViewerPlugin::log_filter`](crate::LogViewerPlugin::log_filter)
+/// to set the initial directives of the runtime filter.
+pub fn reloadable_log_capture_layer(app: &mut App) -> Option<BoxedLayer> {
+    LogCaptureLayer::builder()
+        .reloadable_filter(true)
+        .build(app)
+}
+
+/// Puts the runtime filter in front of `layer`.
+pub(crate) fn with_reloadable_filter(app: &mut App, layer: BoxedLayer) -> BoxedLayer {
+    let (filter, handle) = reload::Layer::new(EnvFilter::new(INITIAL_DIRECTIVES));
+
+    app.insert_resource(LogFilter {
+        directives: INITIAL_DIRECTIVES.to_string(),
+        handle,
+    });
+
+    filter.and_then(layer).boxed()
+}
+
+/// The global tracing filter installed by [`reloadable_log_capture_layer`].
+#[derive(Resource)]
+pub struct LogFilter {
+    directives: String,
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogFilter {
+    /// The `EnvFilter` directives currently in effect.
+    pub fn directives(&self) -> &str {
+        &self.directives
+    }
+
+    /// Replaces the filter, keeping the current one if `directives` are invalid.
+    pub(crate) fn set(&mut self, directives: &str) -> Result<(), String> {
+        // An empty filter would only let errors through, which is never what clearing the field
+        // is meant to do.
+        let filter = match directives.trim() {
+            "" => EnvFilter::new(INITIAL_DIRECTIVES),
+            directives => EnvFilter::try_new(directives).map_err(|error| error.to_string())?,
+        };
+        self.handle
+            .reload(filter)
+            .map_err(|error| error.to_string())?;
+        self.directives = directives.to_string();
+        Ok(())
+    }
+}
+
+/// Replaces the global tracing filter with new `EnvFilter` directives, e.g. `info,game=trace`.
+///
+/// Requires [`reloadable_log_capture_layer`] as `LogPlugin::custom_layer`. Invalid directives
+/// leave the current filter in place.
+#[derive(Event, Debug, Clone, Default)]
+pub struct SetLogFilter(pub String);
+
+pub(crate) fn handle_set_log_filter(
+    trigger: On<SetLogFilter>,
+    log_filter: Option<ResMut<LogFilter>>,
+    mut input_query: Query<&mut TextInput, With<LogFilterInputMarker>>,
+    mut error_query: Query<(&mut Text, &mut Node), With<LogFilterErrorMarker>>,
+) {
+    let Some(mut log_filter) = log_filter else {
+        warn!("`SetLogFilter` requires `reloadable_log_capture_layer` to be installed");
+        return;
+    };
+    let SetLogFilter(directives) = trigger.event();
+
+    let error = log_filter.set(directives).err();
+
+    for mut input in input_query.iter_mut() {
+        if input.invalid != error.is_some() {
+            input.invalid = error.is_some();
+        }
+    }
+    for (mut error_text, mut node) in error_query.iter_mut() {
+        match &error {
+            Some(error) => {
+                error_text.0.clone_from(error);
+                node.display = Display::Flex;
+            }
+            None => node.display = Display::None,
+        }
+    }
+}
+
+/// Shows the log filter field once the runtime filter is installed and keeps it in sync with
+/// the active directives.
+pub(crate) fn sync_log_filter_input(
+    log_filter: Option<Res<LogFilter>>,
+    mut row_query: Query<&mut Node, With<LogFilterRowMarker>>,
+    mut input_query: Query<&mut TextInput, With<LogFilterInputMarker>>,
+) {
+    let Some(log_filter) = log_filter.filter(|log_filter| log_filter.is_changed()) else {
+        return;
+    };
+
+    for mut node in row_query.iter_mut() {
+        if node.display != Display::Flex {
+            node.display = Display::Flex;
+        }
+    }
+    for mut input in input_query.iter_mut() {
+        if !input.focused && input.value != log_filter.directives {
+            input.value.clone_from(&log_filter.directives);
+        }
+    }
+}
+
+/// Applies the log filter field once editing is done, as every intermediate state of the
+/// directives would otherwise be installed while typing.
+pub(crate) fn on_log_filter_input(
+    input_query: Query<&TextInput, (Changed<TextInput>, With<LogFilterInputMarker>)>,
+    log_filter: Option<Res<LogFilter>>,
+    mut commands: Commands,
+) {
+    let Some(log_filter) = log_filter else {
+        return;
+    };
+    for input in input_query.iter() {
+        if !input.focused && input.value != log_filter.directives {
+            commands.trigger(SetLogFilter(input.value.clone()));
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Keyboard shortcuts controlling the viewer.
+
+use crate::{
+    log_list::{self, FilteredLogs},
+    log_viewer::{ChipToggle, LevelFilterChip, ListContainerMarker, LogViewerState, ScrollState},
+    utils::TextInput,
+    ClearLogs, LogFontSize, LogViewerSize, LogViewerVisibility,
+};
+use bevy_ecs::prelude::*;
+use bevy_input::{keyboard::KeyCode, ButtonInput};
+use bevy_ui::prelude::*;
+
+/// A key pressed together with a set of modifiers, e.g. Ctrl+L.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    /// Control, or Cmd on macOS.
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    pub const fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub const fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub const fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub const fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Whether the key was pressed this frame with exactly the binding's modifiers held.
+    fn just_pressed(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        let ctrl = keys.any_pressed([
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ]);
+        let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let alt = keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+        keys.just_pressed(self.key) && ctrl == self.ctrl && shift == self.shift && alt == self.alt
+    }
+}
+
+/// Keyboard shortcuts of the viewer, set via
+/// [`LogViewerPlugin::key_bindings`](crate::LogViewerPlugin::key_bindings) or by changing the
+/// resource. `None` disables a shortcut.
+///
+/// Only `toggle` works while the viewer is hidden, so the other shortcuts don't collide with the
+/// app's controls. No shortcut works while typing into one of the viewer's text fields.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct LogViewerKeyBindings {
+    /// Triggers [`LogViewerVisibility::Toggle`], backtick by default.
+    pub toggle: Option<KeyBinding>,
+    /// Triggers [`ClearLogs`], Ctrl+L by default.
+    pub clear: Option<KeyBinding>,
+    /// Triggers [`LogViewerSize::Toggle`], Alt+F by default.
+    pub fullscreen: Option<KeyBinding>,
+    /// Toggle the level chips from error to trace, Alt+1 to Alt+5 by default.
+    pub levels: [Option<KeyBinding>; 5],
+    /// Scrolls to the oldest line, Home by default.
+    pub scroll_top: Option<KeyBinding>,
+    /// Scrolls to the newest line and follows new ones, End by default.
+    pub scroll_bottom: Option<KeyBinding>,
+    /// Scrolls up by a page, Page Up by default.
+    pub page_up: Option<KeyBinding>,
+    /// Scrolls down by a page, Page Down by default.
+    pub page_down: Option<KeyBinding>,
+}
+
+impl Default for LogViewerKeyBindings {
+    fn default() -> Self {
+        Self {
+            toggle: Some(KeyBinding::new(KeyCode::Backquote)),
+            clear: Some(KeyBinding::new(KeyCode::KeyL).ctrl()),
+            fullscreen: Some(KeyBinding::new(KeyCode::KeyF).alt()),
+            levels: [
+                Some(KeyBinding::new(KeyCode::Digit1).alt()),
+                Some(KeyBinding::new(KeyCode::Digit2).alt()),
+                Some(KeyBinding::new(KeyCode::Digit3).alt()),
+                Some(KeyBinding::new(KeyCode::Digit4).alt()),
+                Some(KeyBinding::new(KeyCode::Digit5).alt()),
+            ],
+            scroll_top: Some(KeyBinding::new(KeyCode::Home)),
+            scroll_bottom: Some(KeyBinding::new(KeyCode::End)),
+            page_up: Some(KeyBinding::new(KeyCode::PageUp)),
+            page_down: Some(KeyBinding::new(KeyCode::PageDown)),
+        }
+    }
+}
+
+impl LogViewerKeyBindings {
+    /// No shortcuts at all.
+    pub fn none() -> Self {
+        Self {
+            toggle: None,
+            clear: None,
+            fullscreen: None,
+            levels: [None; 5],
+            scroll_top: None,
+            scroll_bottom: None,
+            page_up: None,
+            page_down: None,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn handle_key_bindings(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    bindings: Res<LogViewerKeyBindings>,
+    inputs: Query<&TextInput>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+    filtered: Res<FilteredLogs>,
+    font_size: Res<LogFontSize>,
+    mut scroll_query: Query<(&mut ScrollPosition, &ComputedNode), With<ListContainerMarker>>,
+    mut commands: Commands,
+) {
+    // Headless apps have no keyboard.
+    let Some(keys) = keys else {
+        return;
+    };
+    if inputs.iter().any(|input| input.focused) {
+        return;
+    }
+    let pressed = |binding: &Option<KeyBinding>| binding.is_some_and(|b| b.just_pressed(&keys));
+
+    if pressed(&bindings.toggle) {
+        commands.trigger(LogViewerVisibility::Toggle);
+        return;
+    }
+    if !log_viewer_res.visible {
+        return;
+    }
+
+    if pressed(&bindings.clear) {
+        commands.trigger(ClearLogs);
+    }
+    if pressed(&bindings.fullscreen) {
+        commands.trigger(LogViewerSize::Toggle);
+    }
+    let chips = [
+        LevelFilterChip::Error,
+        LevelFilterChip::Warn,
+        LevelFilterChip::Info,
+        LevelFilterChip::Debug,
+        LevelFilterChip::Trace,
+    ];
+    for (binding, chip) in bindings.levels.iter().zip(chips) {
+        if pressed(binding) {
+            commands.trigger(ChipToggle(chip));
+        }
+    }
+
+    if pressed(&bindings.scroll_bottom) {
+        commands.trigger(crate::logging::ScrollToBottom);
+        return;
+    }
+    let Ok((mut scroll_position, container)) = scroll_query.single_mut() else {
+        return;
+    };
+    let row_height = font_size.row_height();
+    let page = (container.size().y * container.inverse_scale_factor - row_height).max(0.);
+    let max = log_list::max_scroll_y(container, filtered.len(), row_height);
+    let y = if pressed(&bindings.scroll_top) {
+        0.
+    } else if pressed(&bindings.page_up) {
+        scroll_position.y - page
+    } else if pressed(&bindings.page_down) {
+        scroll_position.y + page
+    } else {
+        return;
+    };
+    scroll_position.y = y.clamp(0., max);
+    log_viewer_res.scroll_state = ScrollState::Manual;
+}
@@ -1,6 +1,31 @@
+#[cfg(feature = "clipboard")]
+mod clipboard;
 mod debug_log_level;
+mod dock;
+mod export;
+mod gesture;
+mod global_filter;
+mod keybindings;
+mod log_detail;
+mod log_file;
+mod log_filter;
+mod log_list;
+mod log_queue;
+mod log_store;
 mod log_viewer;
 mod logging;
+mod rate_limit;
+mod theme;
 mod utils;
+mod zoom;
 
+pub use dock::LogViewerDock;
+pub use export::{ExportFormat, ExportLogs};
+pub use gesture::{ScreenCorner, ToggleGesture};
+pub use global_filter::{reloadable_log_capture_layer, LogFilter, SetLogFilter};
+pub use keybindings::{KeyBinding, LogViewerKeyBindings};
+pub use log_file::{LogFileSink, DEFAULT_LOG_FILE_COUNT, DEFAULT_LOG_FILE_SIZE};
+pub use log_queue::{OverflowPolicy, DEFAULT_CHANNEL_CAPACITY};
+pub use log_store::{LogStore, DEFAULT_LOG_CAPACITY};
 pub use logging::*;
+pub use theme::{LogFontSize, LogViewerTheme};
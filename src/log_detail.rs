@@ -0,0 +1,151 @@
+//! The panel showing everything captured about a single log, opened by tapping its row.
+
+use crate::{
+    log_list::{format_timestamp, LogRow},
+    log_viewer::{
+        DetailCloseButton, DetailPanelMarker, DetailScrollMarker, DetailTextMarker, LogViewerState,
+    },
+    LogEvent, LogStore,
+};
+use bevy_ecs::prelude::*;
+#[cfg(feature = "clipboard")]
+use bevy_input::{keyboard::KeyCode, ButtonInput};
+use bevy_picking::prelude::*;
+use bevy_ui::prelude::*;
+
+pub(crate) fn on_log_row_click(
+    click: On<Pointer<Click>>,
+    row_query: Query<&LogRow>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+    log_store: Res<LogStore>,
+    #[cfg(feature = "clipboard")] keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut text_query: Query<&mut Text, With<DetailTextMarker>>,
+    mut scroll_query: Query<&mut ScrollPosition, With<DetailScrollMarker>>,
+) {
+    // Releasing the pointer after dragging the list isn't a tap.
+    if click.button != PointerButton::Primary || log_viewer_res.list_dragged {
+        return;
+    }
+    let Ok(row) = row_query.get(click.event().entity) else {
+        return;
+    };
+
+    // Ctrl or Cmd tapping selects lines for copying instead, all of a row's collapsed repeats.
+    #[cfg(feature = "clipboard")]
+    {
+        let modifiers = [
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ];
+        if keys.is_some_and(|keys| keys.any_pressed(modifiers)) {
+            let Some(ids) = row.ids() else {
+                return;
+            };
+            let selected = &mut log_viewer_res.selected;
+            if selected.contains(ids.start()) {
+                ids.for_each(|id| {
+                    selected.remove(&id);
+                });
+            } else {
+                selected.extend(ids);
+            }
+            return;
+        }
+    }
+
+    let Some((id, record)) = row
+        .first_id()
+        .and_then(|id| log_store.get(id).map(|record| (id, record)))
+    else {
+        return;
+    };
+    log_viewer_res.detail_id = Some(id);
+
+    for mut text in text_query.iter_mut() {
+        text.0 = detail_text(record);
+    }
+    for mut scroll_position in scroll_query.iter_mut() {
+        scroll_position.y = 0.;
+    }
+}
+
+pub(crate) fn on_detail_close_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<DetailCloseButton>)>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+) {
+    for interaction in interaction_query.iter() {
+        if matches!(*interaction, Interaction::Pressed) {
+            log_viewer_res.detail_id = None;
+        }
+    }
+}
+
+/// Shows the detail panel while its log is stored, and forgets the log once it was cleared or
+/// evicted.
+pub(crate) fn sync_detail_panel(
+    mut log_viewer_res: ResMut<LogViewerState>,
+    log_store: Res<LogStore>,
+    mut panel_query: Query<&mut Node, With<DetailPanelMarker>>,
+) {
+    if log_viewer_res
+        .detail_id
+        .is_some_and(|id| log_store.get(id).is_none())
+    {
+        log_viewer_res.detail_id = None;
+    }
+    let display = if log_viewer_res.detail_id.is_some() {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in panel_query.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
+fn detail_text(record: &LogEvent) -> String {
+    let mut lines = vec![
+        format!("Timestamp: {}", format_timestamp(&record.timestamp)),
+        format!("Level: {}", record.level),
+        format!("Target: {}", record.target),
+    ];
+    if let Some(module_path) = &record.module_path {
+        lines.push(format!("Module: {module_path}"));
+    }
+    match (&record.file, record.line) {
+        (Some(file), Some(line)) => lines.push(format!("Location: {file}:{line}")),
+        (Some(file), None) => lines.push(format!("Location: {file}")),
+        _ => {}
+    }
+    if record.previous_session {
+        lines.push("From the previous session".to_string());
+    }
+
+    if let Some(message) = &record.message {
+        lines.push(String::new());
+        lines.push("Message:".to_string());
+        lines.push(message.clone());
+    }
+    if !record.fields.is_empty() {
+        lines.push(String::new());
+        lines.push("Fields:".to_string());
+        for (name, value) in &record.fields {
+            lines.push(format!("  {name} = {value}"));
+        }
+    }
+    if !record.spans.is_empty() {
+        lines.push(String::new());
+        lines.push("Spans, outermost first:".to_string());
+        for span in &record.spans {
+            lines.push(format!("  {}", span.name));
+            for (name, value) in &span.fields {
+                lines.push(format!("    {name} = {value}"));
+            }
+        }
+    }
+    lines.join("\n")
+}
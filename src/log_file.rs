@@ -0,0 +1,332 @@
+//! A rotating log file written alongside the capture of logs for the viewer, so logs survive a
+//! crash of the app.
+
+use crate::{export::json_line, FieldValue, LogEvent, SpanContext};
+use bevy_ecs::prelude::*;
+use std::{
+    borrow::Cow,
+    fs::{self, File},
+    io::{self, Write},
+    path::PathBuf,
+};
+use time::{format_description::well_known::Iso8601, OffsetDateTime};
+
+/// Default size in bytes after which the log file is rotated.
+pub const DEFAULT_LOG_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Default number of log files kept, including the one currently written.
+pub const DEFAULT_LOG_FILE_COUNT: usize = 3;
+
+/// Configuration of the log file written by
+/// [`log_capture_layer_with_file`](crate::log_capture_layer_with_file).
+///
+/// Logs are written as JSON Lines to `{dir}/{name}.jsonl`. Once that file exceeds the size limit
+/// it is renamed to `{name}.1.jsonl`, the previous `{name}.1.jsonl` to `{name}.2.jsonl` and so on,
+/// deleting the oldest file beyond the count limit. Every launch starts a new file the same way,
+/// so `{name}.1.jsonl` holds the end of the previous session.
+#[derive(Resource, Debug, Clone)]
+pub struct LogFileSink {
+    dir: PathBuf,
+    name: String,
+    max_file_size: u64,
+    max_files: usize,
+}
+
+impl LogFileSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            name: "bevy_debug_log".to_string(),
+            max_file_size: DEFAULT_LOG_FILE_SIZE,
+            max_files: DEFAULT_LOG_FILE_COUNT,
+        }
+    }
+
+    /// Base name of the log files, `bevy_debug_log` by default.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Size in bytes after which the log file is rotated.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// Number of log files kept, including the one currently written. At least one.
+    pub fn max_files(mut self, count: usize) -> Self {
+        self.max_files = count.max(1);
+        self
+    }
+
+    /// Path of the current log file for `index` 0, or of the `index`th most recent rotated one.
+    pub fn path(&self, index: usize) -> PathBuf {
+        let file_name = match index {
+            0 => format!("{}.jsonl", self.name),
+            index => format!("{}.{index}.jsonl", self.name),
+        };
+        self.dir.join(file_name)
+    }
+
+    /// Shifts every log file one index up, dropping the oldest.
+    fn rotate(&self) -> io::Result<()> {
+        for index in (0..self.max_files).rev() {
+            let path = self.path(index);
+            if !path.exists() {
+                continue;
+            }
+            if index + 1 >= self.max_files {
+                fs::remove_file(path)?;
+            } else {
+                fs::rename(path, self.path(index + 1))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The log file currently written.
+pub(crate) struct RotatingFile {
+    sink: LogFileSink,
+    file: File,
+    size: u64,
+    /// Whether a write failed before, so only the first failure is reported.
+    failed: bool,
+    /// The first failure, until taken to be reported.
+    error: Option<io::Error>,
+}
+
+impl RotatingFile {
+    /// Starts a new log file, rotating the previous session's.
+    pub(crate) fn open(sink: LogFileSink) -> io::Result<Self> {
+        fs::create_dir_all(&sink.dir)?;
+        // Rotate even an empty file, so the first rotated file is always the previous session's.
+        if sink.path(0).exists() {
+            sink.rotate()?;
+        }
+        let file = File::create(sink.path(0))?;
+        Ok(Self {
+            sink,
+            file,
+            size: 0,
+            failed: false,
+            error: None,
+        })
+    }
+
+    /// Writes the record, keeping the first failure for [`Self::take_error`] since failures
+    /// can't be logged from within the logger.
+    pub(crate) fn write(&mut self, record: &LogEvent) {
+        if let Err(error) = self.try_write(record) {
+            if !self.failed {
+                self.failed = true;
+                self.error = Some(error);
+            }
+        }
+    }
+
+    /// The first failed write, once.
+    pub(crate) fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    /// Path of the file currently written.
+    pub(crate) fn path(&self) -> PathBuf {
+        self.sink.path(0)
+    }
+
+    fn try_write(&mut self, record: &LogEvent) -> io::Result<()> {
+        let mut line = json_line(record).to_string();
+        line.push('\n');
+
+        if self.size > 0 && self.size + line.len() as u64 > self.sink.max_file_size {
+            self.sink.rotate()?;
+            self.file = File::create(self.sink.path(0))?;
+            self.size = 0;
+        }
+
+        // Unbuffered, so every line is on disk before the app gets a chance to crash.
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+/// Reads the end of the previous session's logs, skipping lines that can't be parsed, like a last
+/// line cut off by a crash.
+pub(crate) fn read_previous_session(sink: &LogFileSink) -> io::Result<Vec<LogEvent>> {
+    let content = fs::read_to_string(sink.path(1))?;
+    Ok(content.lines().filter_map(parse_line).collect())
+}
+
+/// Parses a line written by [`json_line`].
+fn parse_line(line: &str) -> Option<LogEvent> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+
+    let string = |value: &serde_json::Value| value.as_str().map(|s| Cow::Owned(s.to_string()));
+    let fields = |value: &serde_json::Value| {
+        value
+            .as_object()
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|(name, value)| (Cow::Owned(name.clone()), FieldValue::from_json(value)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let spans = value["spans"]
+        .as_array()
+        .map(|spans| {
+            spans
+                .iter()
+                .map(|span| SpanContext {
+                    name: string(&span["name"]).unwrap_or_default(),
+                    fields: fields(&span["fields"]),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(LogEvent {
+        message: value["message"].as_str().map(str::to_string),
+        fields: fields(&value["fields"]),
+        spans,
+        level: value["level"].as_str()?.parse().ok()?,
+        target: string(&value["target"])?,
+        module_path: string(&value["module_path"]),
+        file: string(&value["file"]),
+        line: value["line"].as_u64().and_then(|line| line.try_into().ok()),
+        timestamp: OffsetDateTime::parse(value["timestamp"].as_str()?, &Iso8601::PARSING).ok()?,
+        previous_session: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_log::Level;
+    use pretty_assertions::assert_eq;
+
+    /// An empty directory for the test's log files.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("bevy_debug_log_test_{}_{name}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    fn lines(path: PathBuf) -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .filter_map(parse_line)
+            .map(|record| record.message.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn json_line_round_trips() {
+        let mut record = LogEvent::test(Level::WARN, "game::enemy", "spawned");
+        record.fields = vec![
+            ("id".into(), FieldValue::I64(3)),
+            ("alive".into(), FieldValue::Bool(true)),
+            ("name".into(), FieldValue::Str("bob".to_string())),
+        ];
+        record.spans = vec![SpanContext {
+            name: "level_load".into(),
+            fields: vec![("level".into(), FieldValue::F64(2.5))],
+        }];
+        record.module_path = Some("game::enemy".into());
+        record.file = Some("src/enemy.rs".into());
+        record.line = Some(42);
+
+        let parsed = parse_line(&json_line(&record).to_string()).unwrap();
+
+        assert_eq!(parsed.message, record.message);
+        assert_eq!(parsed.fields, record.fields);
+        assert_eq!(parsed.spans_text(), "level_load{level=2.5}");
+        assert_eq!(parsed.level, record.level);
+        assert_eq!(parsed.target, record.target);
+        assert_eq!(parsed.module_path, record.module_path);
+        assert_eq!(parsed.file, record.file);
+        assert_eq!(parsed.line, record.line);
+        assert_eq!(parsed.timestamp, record.timestamp);
+        assert!(parsed.previous_session);
+    }
+
+    #[test]
+    fn json_line_keeps_field_types() {
+        let mut record = LogEvent::test(Level::INFO, "game", "hit");
+        record.fields = vec![
+            ("damage".into(), FieldValue::I64(-3)),
+            ("critical".into(), FieldValue::Bool(false)),
+            ("name".into(), FieldValue::Str("bob".to_string())),
+            (
+                "position".into(),
+                FieldValue::Text("Vec2(1.0, 2.0)".to_string()),
+            ),
+        ];
+        record.timestamp = OffsetDateTime::UNIX_EPOCH + time::Duration::nanoseconds(123_456_789);
+
+        let line = json_line(&record);
+
+        assert_eq!(
+            line["fields"],
+            serde_json::json!({
+                "damage": -3,
+                "critical": false,
+                "name": "bob",
+                "position": "Vec2(1.0, 2.0)",
+            })
+        );
+        assert_eq!(line["timestamp"], "1970-01-01T00:00:00.123456789Z");
+    }
+
+    #[test]
+    fn parse_line_skips_cut_off_lines() {
+        let line = json_line(&LogEvent::test(Level::INFO, "game", "cut off")).to_string();
+        assert!(parse_line(&line[..line.len() / 2]).is_none());
+    }
+
+    #[test]
+    fn open_rotates_previous_session() {
+        let dir = test_dir("open");
+        let sink = LogFileSink::new(&dir);
+
+        let mut file = RotatingFile::open(sink.clone()).unwrap();
+        file.write(&LogEvent::test(Level::INFO, "game", "first session"));
+        drop(file);
+        let mut file = RotatingFile::open(sink.clone()).unwrap();
+        file.write(&LogEvent::test(Level::INFO, "game", "second session"));
+
+        assert_eq!(lines(sink.path(0)), vec!["second session"]);
+        assert_eq!(lines(sink.path(1)), vec!["first session"]);
+        let previous: Vec<_> = read_previous_session(&sink)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.message.unwrap())
+            .collect();
+        assert_eq!(previous, vec!["first session"]);
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn write_rotates_full_file_and_drops_oldest() {
+        let dir = test_dir("write");
+        // Small enough that every line starts a new file.
+        let sink = LogFileSink::new(&dir).max_file_size(1).max_files(2);
+
+        let mut file = RotatingFile::open(sink.clone()).unwrap();
+        for message in ["a", "b", "c"] {
+            file.write(&LogEvent::test(Level::INFO, "game", message));
+        }
+
+        assert!(file.take_error().is_none());
+        assert_eq!(lines(sink.path(0)), vec!["c"]);
+        assert_eq!(lines(sink.path(1)), vec!["b"]);
+        assert!(!sink.path(2).exists());
+        fs::remove_dir_all(dir).ok();
+    }
+}
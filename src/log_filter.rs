@@ -0,0 +1,193 @@
+use crate::LogEvent;
+use bevy_ecs::prelude::*;
+use bevy_log::{tracing::level_filters::LevelFilter, Level};
+use regex::Regex;
+use std::{borrow::Cow, collections::BTreeMap, str::FromStr};
+
+/// The search filter of the viewer, set through the search box or [`LogViewerSearch`](crate::LogViewerSearch).
+#[derive(Default)]
+pub(crate) enum SearchFilter {
+    #[default]
+    None,
+    /// Case-insensitive substring, kept lowercased, matched against message, target and fields.
+    Text(String),
+    /// Pattern matched against the line as `target: message key=value`.
+    Regex(Regex),
+}
+
+impl SearchFilter {
+    pub(crate) fn new(query: &str, regex: bool) -> Result<Self, regex::Error> {
+        Ok(if query.is_empty() {
+            SearchFilter::None
+        } else if regex {
+            SearchFilter::Regex(Regex::new(query)?)
+        } else {
+            SearchFilter::Text(query.to_lowercase())
+        })
+    }
+
+    pub(crate) fn matches(&self, record: &LogEvent) -> bool {
+        match self {
+            SearchFilter::None => true,
+            SearchFilter::Text(search) => {
+                record.target.to_lowercase().contains(search)
+                    || record
+                        .message
+                        .as_ref()
+                        .is_some_and(|message| message.to_lowercase().contains(search))
+                    || record.fields.iter().any(|(name, value)| {
+                        name.to_lowercase().contains(search)
+                            || value.to_string().to_lowercase().contains(search)
+                    })
+            }
+            SearchFilter::Regex(regex) => {
+                let mut line = format!("{}: ", record.target);
+                if let Some(message) = &record.message {
+                    line.push_str(message);
+                }
+                if !record.fields.is_empty() {
+                    line.push(' ');
+                    line.push_str(&record.fields_text());
+                }
+                regex.is_match(&line)
+            }
+        }
+    }
+}
+
+/// `EnvFilter`-style directives such as `game=debug,wgpu=off`, deciding which targets and levels
+/// the viewer shows. A directive without a target sets the level for all other targets, a target
+/// without a level shows all of its lines.
+#[derive(Default, Clone, Debug)]
+pub(crate) struct TargetDirectives {
+    default: Option<LevelFilter>,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl TargetDirectives {
+    pub(crate) fn enabled(&self, target: &str, level: Level) -> bool {
+        // The most specific directive, i.e. the longest matching target prefix, wins.
+        let filter = self
+            .targets
+            .iter()
+            .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, filter)| *filter)
+            .or(self.default)
+            .unwrap_or(LevelFilter::TRACE);
+        level <= filter
+    }
+}
+
+impl FromStr for TargetDirectives {
+    type Err = String;
+
+    fn from_str(directives: &str) -> Result<Self, Self::Err> {
+        let mut parsed = TargetDirectives::default();
+        for directive in directives
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+        {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let level = level
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("invalid level in `{directive}`"))?;
+                    parsed.targets.push((target.trim().to_string(), level));
+                }
+                // A bare level, or a bare target that shows everything.
+                None => match directive.parse() {
+                    Ok(level) => parsed.default = Some(level),
+                    Err(_) => parsed
+                        .targets
+                        .push((directive.to_string(), LevelFilter::TRACE)),
+                },
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// All targets seen so far, with the number of their lines in the [`LogStore`](crate::LogStore).
+#[derive(Resource, Default)]
+pub(crate) struct LogTargets(BTreeMap<Cow<'static, str>, usize>);
+
+impl LogTargets {
+    pub(crate) fn add(&mut self, target: Cow<'static, str>) {
+        *self.0.entry(target).or_default() += 1;
+    }
+
+    pub(crate) fn remove(&mut self, target: &str) {
+        if let Some(count) = self.0.get_mut(target) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    /// Resets all counts, but keeps listing the targets.
+    pub(crate) fn clear(&mut self) {
+        self.0.values_mut().for_each(|count| *count = 0);
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&Cow<'static, str>, usize)> {
+        self.0.iter().map(|(target, count)| (target, *count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directives(text: &str) -> TargetDirectives {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn empty_directives_enable_everything() {
+        let directives = directives("");
+        assert!(directives.enabled("game", Level::TRACE));
+        assert!(directives.enabled("wgpu", Level::ERROR));
+    }
+
+    #[test]
+    fn target_directive_sets_its_level() {
+        let directives = directives("game=info, wgpu=off");
+        assert!(directives.enabled("game", Level::WARN));
+        assert!(directives.enabled("game::enemy", Level::INFO));
+        assert!(!directives.enabled("game", Level::DEBUG));
+        assert!(!directives.enabled("wgpu_core", Level::ERROR));
+        assert!(directives.enabled("bevy_ecs", Level::TRACE));
+    }
+
+    #[test]
+    fn bare_level_sets_default() {
+        let directives = directives("warn,game=trace");
+        assert!(!directives.enabled("bevy_ecs", Level::INFO));
+        assert!(directives.enabled("bevy_ecs", Level::WARN));
+        assert!(directives.enabled("game", Level::TRACE));
+    }
+
+    #[test]
+    fn bare_target_shows_all_its_lines() {
+        let directives = directives("error,game");
+        assert!(directives.enabled("game", Level::TRACE));
+        assert!(!directives.enabled("bevy_ecs", Level::WARN));
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let directives = directives("game=error,game::ai=debug");
+        assert!(directives.enabled("game::ai::path", Level::DEBUG));
+        assert!(!directives.enabled("game::ai::path", Level::TRACE));
+        assert!(!directives.enabled("game::render", Level::WARN));
+    }
+
+    #[test]
+    fn invalid_level_is_rejected() {
+        assert_eq!(
+            "game=loud".parse::<TargetDirectives>().unwrap_err(),
+            "invalid level in `game=loud`"
+        );
+    }
+}
@@ -0,0 +1,519 @@
+//! The virtualized log list.
+//!
+//! Log records live in the [`LogStore`]; [`FilteredLogs`] holds the ids of the records that pass
+//! the viewer's filters, grouped into rows of repeated records when collapsing is enabled. Only a
+//! small pool of [`LogRow`] entities is spawned, sized to the viewport, and on every frame the
+//! rows are moved to and refilled with the records currently scrolled into view. All rows share
+//! the same height, so the list's total height and the range of visible records follow directly
+//! from the scroll position.
+//!
+//! Rows don't wrap; the list is as wide as the widest row in view and scrolls horizontally.
+
+use crate::{
+    debug_log_level::DebugLogLevel,
+    log_detail::on_log_row_click,
+    log_viewer::{ListContainerMarker, ListMarker, LogViewerState},
+    LogEvent, LogFontSize, LogStore, LogViewerTheme,
+};
+use bevy_color::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_log::Level;
+use bevy_picking::prelude::*;
+use bevy_text::{prelude::*, LineHeight, TextLayoutInfo};
+use bevy_ui::prelude::*;
+use bevy_utils::prelude::*;
+use std::{collections::VecDeque, num::NonZero};
+use time::{format_description::well_known::iso8601, OffsetDateTime};
+
+/// Text span indices within a row, index 0 being the empty root [`Text`].
+const TIMESTAMP_SPAN: usize = 1;
+const REPEAT_SPAN: usize = 2;
+const LEVEL_SPAN: usize = 3;
+const SPANS_SPAN: usize = 4;
+const TARGET_SPAN: usize = 5;
+const MESSAGE_SPAN: usize = 6;
+const FIELDS_SPAN: usize = 7;
+
+/// Ids of the [`LogStore`] records that pass the viewer's filters, oldest first, and the rows
+/// they are shown in.
+#[derive(Resource, Default)]
+pub(crate) struct FilteredLogs {
+    ids: VecDeque<u64>,
+    rows: VecDeque<FilteredRow>,
+    /// Number of ids popped from the front of `ids` so far, which `FilteredRow::start` counts in.
+    popped: usize,
+    /// Number of records per level passing all but the level filter, indexed by [`level_index`].
+    level_counts: [usize; 5],
+}
+
+/// A run of `len` consecutive `ids`, shown as one row.
+#[derive(Clone, Copy)]
+struct FilteredRow {
+    start: usize,
+    len: usize,
+}
+
+impl FilteredLogs {
+    /// Number of rows.
+    pub(crate) fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Ids of the first and last record shown in the given row, and the row's number of records.
+    fn row(&self, index: usize) -> Option<(u64, u64, usize)> {
+        let row = self.rows.get(index)?;
+        let start = row.start - self.popped;
+        Some((self.ids[start], self.ids[start + row.len - 1], row.len))
+    }
+
+    /// Ids of all records passing the filters, including those collapsed into another's row.
+    #[cfg(feature = "clipboard")]
+    pub(crate) fn ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.ids.iter().copied()
+    }
+
+    pub(crate) fn level_count(&self, level: Level) -> usize {
+        self.level_counts[level_index(level)]
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.ids.clear();
+        self.rows.clear();
+        self.popped = 0;
+        self.level_counts = default();
+    }
+
+    /// Recomputes the view from scratch, e.g. after a filter changed.
+    pub(crate) fn rebuild(&mut self, store: &LogStore, state: &LogViewerState) {
+        self.clear();
+        for (id, record) in store.iter() {
+            self.push(id, record, store, state);
+        }
+    }
+
+    /// Adds a record that is about to be pushed to the store, or is its latest.
+    pub(crate) fn push(
+        &mut self,
+        id: u64,
+        record: &LogEvent,
+        store: &LogStore,
+        state: &LogViewerState,
+    ) {
+        // The level counts include hidden levels, so the chips show what toggling them reveals.
+        if !state.matches(record) {
+            return;
+        }
+        let level = record.level;
+        self.level_counts[level_index(level)] += 1;
+        if !state.level_visible(level) {
+            return;
+        }
+
+        // Only a repeat of the record received right before, which must be shown in the last row,
+        // so lines hidden by the filters in between aren't merged away.
+        let previous = id.checked_sub(1);
+        let repeats_last = state.collapse_repeats
+            && previous.is_some()
+            && self.ids.back().copied() == previous
+            && previous
+                .and_then(|previous| store.get(previous))
+                .is_some_and(|last| last.repeats(record));
+        match self.rows.back_mut() {
+            Some(row) if repeats_last => row.len += 1,
+            _ => self.rows.push_back(FilteredRow {
+                start: self.popped + self.ids.len(),
+                len: 1,
+            }),
+        }
+        self.ids.push_back(id);
+    }
+
+    /// Removes a record the store evicted. Evictions happen oldest first, so any stale ids are
+    /// at the front.
+    pub(crate) fn evict(&mut self, record: &LogEvent, store: &LogStore, state: &LogViewerState) {
+        if state.matches(record) {
+            self.level_counts[level_index(record.level)] -= 1;
+        }
+        while self.ids.front().is_some_and(|id| *id < store.first_id()) {
+            self.ids.pop_front();
+            self.popped += 1;
+            if let Some(row) = self.rows.front_mut() {
+                row.start += 1;
+                row.len -= 1;
+                if row.len == 0 {
+                    self.rows.pop_front();
+                }
+            }
+        }
+    }
+}
+
+fn level_index(level: Level) -> usize {
+    match level {
+        Level::ERROR => 0,
+        Level::WARN => 1,
+        Level::INFO => 2,
+        Level::DEBUG => 3,
+        Level::TRACE => 4,
+    }
+}
+
+/// A pooled row of the log list, showing the records with the given first and last id.
+#[derive(Component, Default)]
+pub(crate) struct LogRow {
+    ids: Option<(u64, u64)>,
+}
+
+impl LogRow {
+    /// Id of the record shown, the first if repeats are collapsed into the row.
+    pub(crate) fn first_id(&self) -> Option<u64> {
+        self.ids.map(|(first, _)| first)
+    }
+
+    /// Ids of all records shown, more than one if repeats are collapsed into the row.
+    #[cfg(feature = "clipboard")]
+    pub(crate) fn ids(&self) -> Option<std::ops::RangeInclusive<u64>> {
+        self.ids.map(|(first, last)| first..=last)
+    }
+}
+
+/// A row without content; [`fill_log_row`] sets its text, colours and font.
+fn log_row(row_height: f32) -> impl Bundle {
+    (
+        Node {
+            display: Display::None,
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            height: Val::Px(row_height),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        Pickable {
+            should_block_lower: false,
+            ..default()
+        },
+        TextLayout::no_wrap(),
+        LineHeight::Px(row_height),
+        Text::default(),
+        BackgroundColor(Color::NONE),
+        LogRow::default(),
+        Name::new("log_row"),
+        children![
+            TextSpan::default(),
+            TextSpan::default(),
+            TextSpan::default(),
+            TextSpan::default(),
+            TextSpan::default(),
+            TextSpan::default(),
+            TextSpan::default(),
+        ],
+    )
+}
+
+/// Fills a row with the first of `count` repeated records, and the timestamp of the `last`.
+fn fill_log_row(
+    writer: &mut TextUiWriter,
+    row: Entity,
+    record: &LogEvent,
+    last: &LogEvent,
+    count: usize,
+    theme: &LogViewerTheme,
+    font_size: &LogFontSize,
+) {
+    let dbg_level = DebugLogLevel::from(record.level);
+
+    let colors = [
+        (TIMESTAMP_SPAN, theme.muted_text),
+        (REPEAT_SPAN, theme.repeat),
+        (LEVEL_SPAN, theme.level_color(record.level)),
+        (SPANS_SPAN, theme.muted_text),
+        (TARGET_SPAN, theme.muted_text),
+        (MESSAGE_SPAN, theme.text),
+        (FIELDS_SPAN, theme.fields),
+    ];
+    for (span, color) in colors {
+        if writer.color(row, span).0 != color {
+            writer.color(row, span).0 = color;
+        }
+        let font = theme.log_font(font_size);
+        if *writer.font(row, span) != font {
+            *writer.font(row, span) = font;
+        }
+    }
+
+    *writer.text(row, TIMESTAMP_SPAN) = format_timestamp(&record.timestamp);
+    *writer.text(row, REPEAT_SPAN) = if count > 1 {
+        format!(" ×{count} until {}", format_timestamp(&last.timestamp))
+    } else {
+        String::new()
+    };
+    *writer.text(row, LEVEL_SPAN) = format!(" {} ", dbg_level);
+    *writer.text(row, SPANS_SPAN) = if record.spans.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", record.spans_text())
+    };
+    *writer.text(row, TARGET_SPAN) = format!("{}: ", record.target);
+    // Events without a message show their fields in place of it.
+    let (message, fields) = match &record.message {
+        Some(message) if record.fields.is_empty() => (message.clone(), String::new()),
+        Some(message) => (message.clone(), format!(" {}", record.fields_text())),
+        None => (record.fields_text(), String::new()),
+    };
+    *writer.text(row, MESSAGE_SPAN) = message;
+    *writer.text(row, FIELDS_SPAN) = fields;
+}
+
+pub(crate) fn format_timestamp(timestamp: &OffsetDateTime) -> String {
+    timestamp
+        .format(&iso8601::Iso8601::<
+            {
+                iso8601::Config::DEFAULT
+                    .set_time_precision(iso8601::TimePrecision::Second {
+                        decimal_digits: Some(NonZero::new(2).unwrap()),
+                    })
+                    .encode()
+            },
+        > {})
+        .unwrap_or("timestamp error".to_string())
+}
+
+/// Largest scroll offset of the list container in logical px, derived from the number of rows
+/// rather than the list's computed size, which lags a frame behind.
+pub(crate) fn max_scroll_y(container: &ComputedNode, row_count: usize, row_height: f32) -> f32 {
+    let viewport = container.size().y * container.inverse_scale_factor;
+    (row_count as f32 * row_height - viewport).max(0.)
+}
+
+/// Sizes the list to the filtered records and the widest row in view, and fills the row pool with
+/// the ones in view.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn sync_log_rows(
+    mut commands: Commands,
+    log_store: Res<LogStore>,
+    filtered: Res<FilteredLogs>,
+    log_viewer_res: Res<LogViewerState>,
+    theme: Res<LogViewerTheme>,
+    font_size: Res<LogFontSize>,
+    container_query: Query<(&ScrollPosition, &ComputedNode), With<ListContainerMarker>>,
+    mut list_query: Query<(Entity, &mut Node), (With<ListMarker>, Without<LogRow>)>,
+    mut row_query: Query<(
+        Entity,
+        &mut LogRow,
+        &mut Node,
+        &mut LineHeight,
+        &mut BackgroundColor,
+        &TextLayoutInfo,
+    )>,
+    mut writer: TextUiWriter,
+) {
+    let (Ok((scroll_position, container)), Ok((list, mut list_node))) =
+        (container_query.single(), list_query.single_mut())
+    else {
+        return;
+    };
+
+    let row_height = font_size.row_height();
+    let height = Val::Px(filtered.len() as f32 * row_height);
+    if list_node.height != height {
+        list_node.height = height;
+    }
+
+    let viewport = container.size().y * container.inverse_scale_factor;
+    let needed = (viewport / row_height).ceil() as usize + 1;
+    let pool_size = row_query.iter().len();
+    for _ in pool_size..needed {
+        commands
+            .spawn((log_row(row_height), ChildOf(list)))
+            .observe(on_log_row_click);
+    }
+
+    let first = (scroll_position.y.max(0.) / row_height) as usize;
+    // Widest row in logical px, as laid out last frame.
+    let mut widest = 0f32;
+    for (slot, (row, mut log_row, mut node, mut line_height, mut background, layout)) in
+        row_query.iter_mut().enumerate()
+    {
+        let index = first + slot;
+        let records = (slot < needed)
+            .then(|| filtered.row(index))
+            .flatten()
+            .and_then(|(first_id, last_id, count)| {
+                let first = log_store.get(first_id)?;
+                let last = log_store.get(last_id)?;
+                Some(((first_id, last_id), first, last, count))
+            });
+
+        let Some((ids, record, last, count)) = records else {
+            if node.display != Display::None {
+                node.display = Display::None;
+            }
+            if log_row.ids.is_some() {
+                log_row.ids = None;
+            }
+            continue;
+        };
+
+        let restyle = theme.is_changed() || font_size.is_changed();
+        if restyle {
+            node.height = Val::Px(row_height);
+            *line_height = LineHeight::Px(row_height);
+        }
+        if layout.scale_factor > 0. {
+            widest = widest.max(layout.size.x / layout.scale_factor);
+        }
+        let top = Val::Px(index as f32 * row_height);
+        if node.top != top || node.display != Display::Flex {
+            node.top = top;
+            node.display = Display::Flex;
+        }
+        if log_row.ids != Some(ids) || restyle {
+            fill_log_row(&mut writer, row, record, last, count, &theme, &font_size);
+            log_row.ids = Some(ids);
+        }
+        // Highlight selected lines, and tint the previous session's to set them apart.
+        let color = if log_viewer_res.is_selected(ids.0) {
+            theme.selection
+        } else if record.previous_session {
+            theme.previous_session
+        } else {
+            Color::NONE
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+
+    let width = Val::Px(widest);
+    if list_node.width != width {
+        list_node.width = width;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct Fixture {
+        store: LogStore,
+        filtered: FilteredLogs,
+        state: LogViewerState,
+    }
+
+    impl Fixture {
+        fn new(capacity: usize) -> Self {
+            Self {
+                store: LogStore::new(capacity),
+                filtered: FilteredLogs::default(),
+                state: LogViewerState::default(),
+            }
+        }
+
+        fn log(&mut self, level: Level, target: &'static str, message: &str) {
+            self.push(LogEvent::test(level, target, message));
+        }
+
+        /// Stores a record the way `store_log` does.
+        fn push(&mut self, record: LogEvent) {
+            let id = self.store.next_id();
+            self.filtered.push(id, &record, &self.store, &self.state);
+            if let Some(evicted) = self.store.push(record) {
+                self.filtered.evict(&evicted, &self.store, &self.state);
+            }
+        }
+
+        /// First id, last id and record count of every row.
+        fn rows(&self) -> Vec<(u64, u64, usize)> {
+            (0..self.filtered.len())
+                .map(|index| self.filtered.row(index).unwrap())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn collapses_consecutive_repeats() {
+        let mut fixture = Fixture::new(10);
+        fixture.log(Level::INFO, "game", "a");
+        fixture.log(Level::INFO, "game", "a");
+        fixture.log(Level::INFO, "game", "b");
+        fixture.log(Level::WARN, "game", "b");
+
+        assert_eq!(fixture.rows(), vec![(0, 1, 2), (2, 2, 1), (3, 3, 1)]);
+    }
+
+    #[test]
+    fn keeps_repeats_apart_without_collapsing() {
+        let mut fixture = Fixture::new(10);
+        fixture.state.collapse_repeats = false;
+        fixture.log(Level::INFO, "game", "a");
+        fixture.log(Level::INFO, "game", "a");
+
+        assert_eq!(fixture.rows(), vec![(0, 0, 1), (1, 1, 1)]);
+    }
+
+    #[test]
+    fn hidden_lines_break_repeats() {
+        let mut fixture = Fixture::new(10);
+        fixture.state.debug_visible = false;
+        fixture.log(Level::INFO, "game", "a");
+        fixture.log(Level::DEBUG, "game", "hidden");
+        fixture.log(Level::INFO, "game", "a");
+
+        assert_eq!(fixture.rows(), vec![(0, 0, 1), (2, 2, 1)]);
+    }
+
+    #[test]
+    fn previous_session_breaks_repeats() {
+        let mut fixture = Fixture::new(10);
+        fixture.push(LogEvent {
+            previous_session: true,
+            ..LogEvent::test(Level::INFO, "game", "a")
+        });
+        fixture.log(Level::INFO, "game", "a");
+
+        assert_eq!(fixture.rows(), vec![(0, 0, 1), (1, 1, 1)]);
+    }
+
+    #[test]
+    fn counts_levels_hidden_by_the_chips() {
+        let mut fixture = Fixture::new(10);
+        fixture.state.debug_visible = false;
+        fixture.state.target_directives = "bevy=off".parse().unwrap();
+        fixture.log(Level::DEBUG, "game", "a");
+        fixture.log(Level::ERROR, "game", "b");
+        fixture.log(Level::ERROR, "bevy_ecs", "filtered");
+
+        assert_eq!(fixture.filtered.level_count(Level::DEBUG), 1);
+        assert_eq!(fixture.filtered.level_count(Level::ERROR), 1);
+        assert_eq!(fixture.rows(), vec![(1, 1, 1)]);
+    }
+
+    #[test]
+    fn eviction_shrinks_and_drops_rows() {
+        let mut fixture = Fixture::new(3);
+        fixture.log(Level::INFO, "game", "a");
+        fixture.log(Level::INFO, "game", "a");
+        fixture.log(Level::INFO, "game", "b");
+        fixture.log(Level::INFO, "game", "c");
+        assert_eq!(fixture.rows(), vec![(1, 1, 1), (2, 2, 1), (3, 3, 1)]);
+        assert_eq!(fixture.filtered.popped, 1);
+
+        fixture.log(Level::INFO, "game", "d");
+        assert_eq!(fixture.rows(), vec![(2, 2, 1), (3, 3, 1), (4, 4, 1)]);
+        assert_eq!(fixture.filtered.popped, 2);
+        assert_eq!(fixture.filtered.level_count(Level::INFO), 3);
+    }
+
+    #[test]
+    fn rebuild_matches_incremental_pushes() {
+        let mut fixture = Fixture::new(10);
+        fixture.log(Level::INFO, "game", "a");
+        fixture.log(Level::INFO, "game", "a");
+        fixture.log(Level::WARN, "game", "b");
+        let rows = fixture.rows();
+
+        fixture.filtered.rebuild(&fixture.store, &fixture.state);
+        assert_eq!(fixture.rows(), rows);
+    }
+}
@@ -0,0 +1,157 @@
+//! The buffer handing captured logs from the capture layer, on any thread, to the viewer.
+//!
+//! Every log briefly locks the buffer on the thread that emitted it; the viewer takes the lock
+//! once a frame to drain it.
+
+use crate::LogEvent;
+use std::{
+    collections::VecDeque,
+    mem,
+    sync::{Mutex, PoisonError},
+};
+
+/// Default number of logs buffered until the viewer receives them, see
+/// [`LogCaptureLayerBuilder::channel_capacity`](crate::LogCaptureLayerBuilder::channel_capacity).
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 10_000;
+
+/// What the capture layer does with new logs while its buffer is full, see
+/// [`LogCaptureLayerBuilder::channel_capacity`](crate::LogCaptureLayerBuilder::channel_capacity).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Keeps the buffered logs and drops new ones.
+    #[default]
+    DropNewest,
+    /// Drops the oldest buffered log to make room for a new one.
+    DropOldest,
+    /// Keeps one in `every` new logs, dropping the oldest buffered log to make room for it, and
+    /// drops the rest. Shows a trace of a log storm rather than only its start or end.
+    Sample { every: usize },
+}
+
+#[derive(Default)]
+struct QueueState {
+    records: VecDeque<LogEvent>,
+    /// Logs dropped since the last drain.
+    dropped: usize,
+    /// Logs arrived while full, for sampling.
+    overflowed: usize,
+}
+
+pub(crate) struct LogQueue {
+    state: Mutex<QueueState>,
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+impl LogQueue {
+    /// A queue holding at most `capacity` logs.
+    pub(crate) fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            state: Mutex::default(),
+            capacity,
+            policy,
+        }
+    }
+
+    pub(crate) fn push(&self, record: LogEvent) {
+        // A panic while holding the lock leaves the queue in a valid state, so carry on.
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if state.records.len() < self.capacity {
+            state.overflowed = 0;
+            state.records.push_back(record);
+            return;
+        }
+
+        state.overflowed += 1;
+        let keep = match self.policy {
+            OverflowPolicy::DropNewest => false,
+            OverflowPolicy::DropOldest => true,
+            OverflowPolicy::Sample { every } => state.overflowed.is_multiple_of(every.max(1)),
+        };
+        state.dropped += 1;
+        if keep {
+            state.records.pop_front();
+            state.records.push_back(record);
+        }
+    }
+
+    /// Takes all buffered logs and the number of logs dropped since the last drain.
+    pub(crate) fn drain(&self) -> (VecDeque<LogEvent>, usize) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let dropped = mem::take(&mut state.dropped);
+        (mem::take(&mut state.records), dropped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_log::Level;
+    use pretty_assertions::assert_eq;
+
+    /// Pushes logs `0..count` and drains them as messages with the dropped count.
+    fn push_and_drain(policy: OverflowPolicy, count: usize) -> (Vec<String>, usize) {
+        let queue = LogQueue::new(3, policy);
+        for i in 0..count {
+            queue.push(LogEvent::test(Level::INFO, "test", &i.to_string()));
+        }
+        let (records, dropped) = queue.drain();
+        let messages = records
+            .into_iter()
+            .map(|record| record.message.unwrap())
+            .collect();
+        (messages, dropped)
+    }
+
+    #[test]
+    fn keeps_everything_within_capacity() {
+        for policy in [
+            OverflowPolicy::DropNewest,
+            OverflowPolicy::DropOldest,
+            OverflowPolicy::Sample { every: 2 },
+        ] {
+            assert_eq!(
+                push_and_drain(policy, 3),
+                (vec!["0".into(), "1".into(), "2".into()], 0)
+            );
+        }
+    }
+
+    #[test]
+    fn drop_newest_keeps_first_logs() {
+        assert_eq!(
+            push_and_drain(OverflowPolicy::DropNewest, 6),
+            (vec!["0".into(), "1".into(), "2".into()], 3)
+        );
+    }
+
+    #[test]
+    fn drop_oldest_keeps_last_logs() {
+        assert_eq!(
+            push_and_drain(OverflowPolicy::DropOldest, 6),
+            (vec!["3".into(), "4".into(), "5".into()], 3)
+        );
+    }
+
+    #[test]
+    fn sample_keeps_every_nth_overflowing_log() {
+        // 3 to 8 overflow, of which 4, 6 and 8 are kept, each evicting the oldest.
+        assert_eq!(
+            push_and_drain(OverflowPolicy::Sample { every: 2 }, 9),
+            (vec!["4".into(), "6".into(), "8".into()], 6)
+        );
+    }
+
+    #[test]
+    fn drain_resets_the_queue() {
+        let queue = LogQueue::new(1, OverflowPolicy::DropNewest);
+        queue.push(LogEvent::test(Level::INFO, "test", "a"));
+        queue.push(LogEvent::test(Level::INFO, "test", "b"));
+        queue.drain();
+
+        let (records, dropped) = queue.drain();
+        assert!(records.is_empty());
+        assert_eq!(dropped, 0);
+    }
+}
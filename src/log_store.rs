@@ -0,0 +1,194 @@
+use crate::LogEvent;
+use bevy_ecs::prelude::*;
+use std::collections::VecDeque;
+
+/// Default number of records kept by [`LogStore`].
+pub const DEFAULT_LOG_CAPACITY: usize = 5_000;
+
+/// The captured log records, held in a ring buffer that grows up to its capacity.
+///
+/// Once the buffer is full every new record evicts the oldest one. Evictions are counted so the
+/// viewer can show how many lines were lost.
+///
+/// Every record gets a sequential id that stays valid until it is evicted, so views over the
+/// store can refer to records without holding on to them.
+#[derive(Resource)]
+pub struct LogStore {
+    records: VecDeque<LogEvent>,
+    capacity: usize,
+    dropped: usize,
+    lost: usize,
+    /// Id of the oldest record, `records[0]`.
+    first_id: u64,
+}
+
+impl LogStore {
+    pub fn new(capacity: usize) -> Self {
+        // A zero capacity would evict every record right away, so keep at least one.
+        let capacity = capacity.max(1);
+        Self {
+            records: VecDeque::new(),
+            capacity,
+            dropped: 0,
+            lost: 0,
+            first_id: 0,
+        }
+    }
+
+    /// Maximum number of records held before the oldest ones are evicted.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of records currently held.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Number of records evicted to make room for newer ones since the last clear.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Number of logs lost before reaching the store because the capture buffer was full, see
+    /// [`LogCaptureLayerBuilder::channel_capacity`](crate::LogCaptureLayerBuilder::channel_capacity).
+    pub fn lost(&self) -> usize {
+        self.lost
+    }
+
+    /// Removes all records and resets the dropped and lost counters.
+    pub fn clear(&mut self) {
+        // Ids are never reused, so anything still pointing at a cleared record finds nothing.
+        self.first_id = self.next_id();
+        self.records.clear();
+        self.dropped = 0;
+        self.lost = 0;
+    }
+
+    /// Id of the oldest record still held.
+    pub(crate) fn first_id(&self) -> u64 {
+        self.first_id
+    }
+
+    /// Id the next pushed record will get.
+    pub(crate) fn next_id(&self) -> u64 {
+        self.first_id + self.records.len() as u64
+    }
+
+    /// The record with the given id, unless it was evicted or cleared.
+    pub fn get(&self, id: u64) -> Option<&LogEvent> {
+        let index = id.checked_sub(self.first_id)?;
+        self.records.get(usize::try_from(index).ok()?)
+    }
+
+    /// Iterates over all records, oldest first, together with their ids.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &LogEvent)> {
+        (self.first_id..).zip(self.records.iter())
+    }
+
+    pub(crate) fn add_lost(&mut self, count: usize) {
+        self.lost += count;
+    }
+
+    /// Appends a record, returning the evicted oldest record if the buffer was full.
+    pub(crate) fn push(&mut self, record: LogEvent) -> Option<LogEvent> {
+        let evicted = if self.records.len() >= self.capacity {
+            self.dropped += 1;
+            self.first_id += 1;
+            self.records.pop_front()
+        } else {
+            None
+        };
+        self.records.push_back(record);
+        evicted
+    }
+}
+
+impl Default for LogStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_log::Level;
+    use pretty_assertions::assert_eq;
+
+    fn record(message: &str) -> LogEvent {
+        LogEvent::test(Level::INFO, "test", message)
+    }
+
+    fn messages(store: &LogStore) -> Vec<(u64, &str)> {
+        store
+            .iter()
+            .map(|(id, record)| (id, record.message().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn push_assigns_sequential_ids() {
+        let mut store = LogStore::new(3);
+        assert!(store.push(record("a")).is_none());
+        assert!(store.push(record("b")).is_none());
+
+        assert_eq!(messages(&store), vec![(0, "a"), (1, "b")]);
+        assert_eq!(store.first_id(), 0);
+        assert_eq!(store.next_id(), 2);
+        assert_eq!(store.get(1).and_then(LogEvent::message), Some("b"));
+        assert!(store.get(2).is_none());
+    }
+
+    #[test]
+    fn push_evicts_oldest_when_full() {
+        let mut store = LogStore::new(2);
+        store.push(record("a"));
+        store.push(record("b"));
+        let evicted = store.push(record("c"));
+
+        assert_eq!(evicted.as_ref().and_then(LogEvent::message), Some("a"));
+        assert_eq!(messages(&store), vec![(1, "b"), (2, "c")]);
+        assert_eq!(store.dropped(), 1);
+        assert!(store.get(0).is_none());
+    }
+
+    #[test]
+    fn clear_keeps_ids_unique() {
+        let mut store = LogStore::new(2);
+        store.push(record("a"));
+        store.push(record("b"));
+        store.push(record("c"));
+        store.add_lost(4);
+        store.clear();
+
+        assert!(store.is_empty());
+        assert_eq!((store.dropped(), store.lost()), (0, 0));
+        assert!(store.get(2).is_none());
+        store.push(record("d"));
+        assert_eq!(messages(&store), vec![(3, "d")]);
+    }
+
+    #[test]
+    fn zero_capacity_keeps_one_record() {
+        let mut store = LogStore::new(0);
+        store.push(record("a"));
+        store.push(record("b"));
+
+        assert_eq!(store.capacity(), 1);
+        assert_eq!(messages(&store), vec![(1, "b")]);
+    }
+
+    #[test]
+    fn huge_capacity_allocates_on_demand() {
+        let mut store = LogStore::new(usize::MAX);
+        store.push(record("a"));
+
+        assert_eq!(store.capacity(), usize::MAX);
+        assert_eq!(messages(&store), vec![(0, "a")]);
+    }
+}
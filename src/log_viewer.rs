@@ -1,12 +1,25 @@
-use crate::{debug_log_level::DebugLogLevel, utils, ScrollToBottom};
+use crate::{
+    debug_log_level::DebugLogLevel,
+    dock::{
+        on_resize_corner_drag, on_resize_handle_drag, on_title_bar_drag, LogViewerDock,
+        ResizeCorner, DEFAULT_BIG_SIZE, DEFAULT_FLOATING_RECT, DEFAULT_SMALL_SIZE,
+    },
+    log_filter::{SearchFilter, TargetDirectives},
+    theme::{ThemeColor, ThemedBackground, ThemedBorder},
+    utils, LogEvent, LogViewerTheme, ScreenCorner, ScrollToBottom,
+};
 use bevy_camera::visibility::RenderLayers;
 use bevy_color::prelude::*;
 use bevy_ecs::prelude::*;
-use bevy_log::tracing::level_filters::LevelFilter;
+use bevy_log::{tracing::level_filters::LevelFilter, Level};
 use bevy_math::prelude::*;
 use bevy_picking::{pointer::PointerId, prelude::*};
+use bevy_text::prelude::*;
 use bevy_ui::prelude::*;
 use bevy_utils::prelude::*;
+#[cfg(feature = "clipboard")]
+use std::collections::BTreeSet;
+use std::{borrow::Cow, collections::HashSet, path::PathBuf};
 
 pub const RENDER_LAYER: usize = 55;
 
@@ -23,14 +36,81 @@ pub(crate) enum ScrollState {
 pub(crate) struct LogViewerState {
     pub(crate) visible: bool,
     pub(crate) fullscreen: bool,
+    pub(crate) dock: LogViewerDock,
+    /// Share of the screen in percent covered while small, restored when leaving fullscreen.
+    pub(crate) small_size: f32,
+    /// Share of the screen in percent covered while fullscreen.
+    pub(crate) big_size: f32,
+    /// Share of the screen in percent covered while small, changed by the resize handle.
+    pub(crate) size: f32,
+    /// Whether the viewer floats at `floating_rect` while not fullscreen.
+    pub(crate) floating: bool,
+    /// Position and size in logical px while floating, kept while docked or hidden.
+    pub(crate) floating_rect: Rect,
+    /// Whether the viewer fills its own window, so it can't float or be resized.
+    pub(crate) own_window: bool,
     pub(crate) auto_open_threshold: LevelFilter,
     pub(crate) auto_open_enabled: bool,
+    pub(crate) capture_without_message: bool,
     pub(crate) error_visible: bool,
     pub(crate) warn_visible: bool,
     pub(crate) info_visible: bool,
     pub(crate) debug_visible: bool,
     pub(crate) trace_visible: bool,
     pub(crate) scroll_state: ScrollState,
+    pub(crate) search: SearchFilter,
+    /// The query and mode `search` was built from.
+    pub(crate) search_query: String,
+    pub(crate) search_regex: bool,
+    /// Id of the log shown in the detail panel.
+    pub(crate) detail_id: Option<u64>,
+    /// Ids of the logs selected for copying, all of a row's if repeats are collapsed into it.
+    #[cfg(feature = "clipboard")]
+    pub(crate) selected: BTreeSet<u64>,
+    /// Whether the pointer moved the list since it was pressed, so releasing it isn't a tap.
+    pub(crate) list_dragged: bool,
+    /// Whether repeated records are collapsed into one row.
+    pub(crate) collapse_repeats: bool,
+    pub(crate) target_directives: TargetDirectives,
+    /// The text `target_directives` was parsed from.
+    pub(crate) target_directives_text: String,
+    /// Targets switched off in the target panel.
+    pub(crate) hidden_targets: HashSet<Cow<'static, str>>,
+    /// Where the export button writes to, the temp dir if unset.
+    pub(crate) export_dir: Option<PathBuf>,
+}
+
+impl LogViewerState {
+    /// Whether the line passes all filters other than the level filter chips.
+    pub(crate) fn matches(&self, record: &LogEvent) -> bool {
+        !self.hidden_targets.contains(&record.target)
+            && self.target_directives.enabled(&record.target, record.level)
+            && self.search.matches(record)
+    }
+
+    /// Whether lines of the given level pass the level filter chips.
+    pub(crate) fn level_visible(&self, level: Level) -> bool {
+        match level {
+            Level::ERROR => self.error_visible,
+            Level::WARN => self.warn_visible,
+            Level::INFO => self.info_visible,
+            Level::DEBUG => self.debug_visible,
+            Level::TRACE => self.trace_visible,
+        }
+    }
+
+    /// Whether the log is selected for copying.
+    pub(crate) fn is_selected(&self, id: u64) -> bool {
+        #[cfg(feature = "clipboard")]
+        {
+            self.selected.contains(&id)
+        }
+        #[cfg(not(feature = "clipboard"))]
+        {
+            let _ = id;
+            false
+        }
+    }
 }
 
 impl Default for LogViewerState {
@@ -39,13 +119,33 @@ impl Default for LogViewerState {
             auto_open_threshold: LevelFilter::OFF,
             visible: false,
             fullscreen: false,
+            dock: LogViewerDock::Top,
+            small_size: DEFAULT_SMALL_SIZE,
+            big_size: DEFAULT_BIG_SIZE,
+            size: DEFAULT_SMALL_SIZE,
+            floating: false,
+            floating_rect: DEFAULT_FLOATING_RECT,
+            own_window: false,
             auto_open_enabled: false,
+            capture_without_message: true,
             error_visible: true,
             warn_visible: true,
             info_visible: true,
             debug_visible: true,
             trace_visible: true,
             scroll_state: ScrollState::Auto,
+            search: SearchFilter::None,
+            search_query: String::new(),
+            search_regex: false,
+            detail_id: None,
+            #[cfg(feature = "clipboard")]
+            selected: BTreeSet::new(),
+            list_dragged: false,
+            collapse_repeats: true,
+            target_directives: TargetDirectives::default(),
+            target_directives_text: String::new(),
+            hidden_targets: HashSet::new(),
+            export_dir: None,
         }
     }
 }
@@ -53,12 +153,108 @@ impl Default for LogViewerState {
 #[derive(Component)]
 pub(crate) struct ListMarker;
 
+/// Strip on the viewer's inner edge that resizes it when dragged.
+#[derive(Component)]
+pub(crate) struct ResizeHandleMarker;
+
+/// The visible bar in the middle of the resize handle.
+#[derive(Component)]
+pub(crate) struct ResizeGripMarker;
+
 #[derive(Component)]
 pub(crate) struct ListContainerMarker;
 
 #[derive(Component)]
 pub(crate) struct GoDownBtnMarker;
 
+/// The free-text search box in the title bar.
+#[derive(Component)]
+pub(crate) struct SearchInputMarker;
+
+/// Chip switching the search box between plain text and regex matching.
+#[derive(Component)]
+pub(crate) struct RegexChip;
+
+/// Panel showing all details of a tapped log line.
+#[derive(Component)]
+pub(crate) struct DetailPanelMarker;
+
+/// Scrolled container of the [`DetailTextMarker`].
+#[derive(Component)]
+pub(crate) struct DetailScrollMarker;
+
+/// Text of the [`DetailPanelMarker`].
+#[derive(Component)]
+pub(crate) struct DetailTextMarker;
+
+/// Button copying the log shown in the [`DetailPanelMarker`] to the clipboard.
+#[cfg(feature = "clipboard")]
+#[derive(Component)]
+pub(crate) struct DetailCopyButton;
+
+/// Title bar button copying the selected log lines to the clipboard, or all shown if none are
+/// selected.
+#[cfg(feature = "clipboard")]
+#[derive(Component)]
+pub(crate) struct CopyButton;
+
+/// Button closing the [`DetailPanelMarker`].
+#[derive(Component)]
+pub(crate) struct DetailCloseButton;
+
+/// Chip switching the collapsing of repeated lines on and off.
+#[derive(Component)]
+pub(crate) struct CollapseChip;
+
+/// Chip opening and closing the [`TargetPanelMarker`].
+#[derive(Component)]
+pub(crate) struct TargetsChip;
+
+/// Collapsible panel below the title bar for filtering by target.
+#[derive(Component)]
+pub(crate) struct TargetPanelMarker;
+
+/// Container of the [`TargetToggle`]s in the target panel.
+#[derive(Component)]
+pub(crate) struct TargetListMarker;
+
+/// Text input for the target filter directives.
+#[derive(Component)]
+pub(crate) struct TargetDirectivesInputMarker;
+
+/// Row of the target panel holding the global log filter field, shown only if
+/// [`reloadable_log_capture_layer`](crate::reloadable_log_capture_layer) is installed.
+#[derive(Component)]
+pub(crate) struct LogFilterRowMarker;
+
+/// Text input for the directives of the global [`LogFilter`](crate::LogFilter).
+#[derive(Component)]
+pub(crate) struct LogFilterInputMarker;
+
+/// Text explaining why the global log filter directives are invalid.
+#[derive(Component)]
+pub(crate) struct LogFilterErrorMarker;
+
+/// Text explaining why the target filter directives are invalid.
+#[derive(Component)]
+pub(crate) struct TargetDirectivesErrorMarker;
+
+/// Button showing or hiding the lines of one target, labelled with the target's line count.
+#[derive(Component)]
+pub(crate) struct TargetToggle(pub(crate) Cow<'static, str>);
+
+/// Title bar text explaining why the search regex is invalid.
+#[derive(Component)]
+pub(crate) struct SearchErrorMarker;
+
+/// Title bar text showing how many lines were evicted from the [`LogStore`](crate::LogStore).
+#[derive(Component)]
+pub(crate) struct DroppedLinesMarker;
+
+/// Title bar text showing how many logs were lost because the capture buffer was full.
+#[derive(Component)]
+pub(crate) struct LostLogsMarker;
+
 #[derive(Component)]
 pub(crate) enum TrafficLightButton {
     Red,
@@ -69,6 +265,14 @@ pub(crate) enum TrafficLightButton {
 #[derive(Component, Clone)]
 pub(crate) struct AutoCheckBox;
 
+/// Title bar button exporting the logs to the export directory.
+#[derive(Component)]
+pub(crate) struct ExportButton;
+
+/// Title bar text reporting the outcome of the last export or copy.
+#[derive(Component)]
+pub(crate) struct StatusTextMarker;
+
 #[derive(Component, Clone, Copy, PartialEq, Debug)]
 pub(crate) enum LevelFilterChip {
     Error,
@@ -78,11 +282,23 @@ pub(crate) enum LevelFilterChip {
     Trace,
 }
 
+impl LevelFilterChip {
+    pub(crate) fn level(self) -> Level {
+        match self {
+            LevelFilterChip::Error => Level::ERROR,
+            LevelFilterChip::Warn => Level::WARN,
+            LevelFilterChip::Info => Level::INFO,
+            LevelFilterChip::Debug => Level::DEBUG,
+            LevelFilterChip::Trace => Level::TRACE,
+        }
+    }
+}
+
 #[derive(Event)]
 pub(crate) struct ChipToggle(pub(crate) LevelFilterChip);
 
 /// A macOS-style window button: a coloured circle in a padded slot.
-fn traffic_light(name: &str, color: Color, button: TrafficLightButton) -> impl Bundle {
+fn traffic_light(name: &str, color: ThemeColor, button: TrafficLightButton) -> impl Bundle {
     (
         Node {
             padding: UiRect::all(Val::Px(5.)),
@@ -98,13 +314,17 @@ fn traffic_light(name: &str, color: Color, button: TrafficLightButton) -> impl B
                 border_radius: BorderRadius::all(Val::Px(20.)),
                 ..default()
             },
-            BackgroundColor(color),
+            ThemedBackground(color),
             button,
         )],
     )
 }
 
-pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewerState>) {
+pub fn setup_log_viewer_ui(
+    mut commands: Commands,
+    log_viewer_res: Res<LogViewerState>,
+    theme: Res<LogViewerTheme>,
+) {
     let safe_zone_top = if cfg!(target_os = "ios") { 50 } else { 0 };
 
     let chips = [
@@ -148,17 +368,17 @@ pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewer
     let traffic_lights = [
         (
             "size_btn",
-            Color::srgb_u8(43, 198, 63),
+            ThemeColor::SizeButton,
             TrafficLightButton::Green,
         ),
         (
             "clear_btn",
-            Color::srgb_u8(255, 188, 46),
+            ThemeColor::ClearButton,
             TrafficLightButton::Yellow,
         ),
         (
             "close_logs_btn",
-            Color::srgb_u8(255, 95, 87),
+            ThemeColor::CloseButton,
             TrafficLightButton::Red,
         ),
     ];
@@ -184,7 +404,7 @@ pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewer
                 border: UiRect::bottom(Val::Px(1.)),
                 ..default()
             },
-            BackgroundColor(Color::srgba(0.15, 0.15, 0.15, 0.75)),
+            ThemedBackground(ThemeColor::Background),
             BorderColor::all(Color::NONE),
         ))
         .with_children(|parent| {
@@ -193,7 +413,10 @@ pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewer
                 .spawn((
                     Node {
                         flex_direction: FlexDirection::Row,
+                        // Narrow screens can't fit all controls on one line.
+                        flex_wrap: FlexWrap::Wrap,
                         justify_content: JustifyContent::SpaceBetween,
+                        flex_shrink: 0.,
                         ..default()
                     },
                     Name::new("title_bar"),
@@ -202,21 +425,89 @@ pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewer
                     for (chip, level, label, active, name) in chips {
                         parent.spawn(utils::chip(
                             chip,
-                            level.into(),
+                            theme.level_color(level.into()),
+                            theme.text,
                             "0".into(),
                             label.into(),
                             active,
                             name,
                         ));
                     }
+                    parent.spawn(utils::toggle_chip(
+                        RegexChip,
+                        ".*".into(),
+                        log_viewer_res.search_regex,
+                        "regex_switch",
+                    ));
+                    parent.spawn(utils::toggle_chip(
+                        CollapseChip,
+                        "×N".into(),
+                        log_viewer_res.collapse_repeats,
+                        "collapse_switch",
+                    ));
+                    parent.spawn(utils::toggle_chip(
+                        TargetsChip,
+                        "Targets".into(),
+                        false,
+                        "targets_switch",
+                    ));
 
                     parent.spawn((
                         Node {
                             align_items: AlignItems::End,
                             flex_grow: 1.0,
+                            max_width: Val::Px(200.),
+                            margin: UiRect::horizontal(Val::Px(4.)),
                             ..default()
                         },
                         Name::new("title_bar_spacer"),
+                        children![utils::text_input(
+                            SearchInputMarker,
+                            "search_input",
+                            "Search"
+                        )],
+                    ));
+
+                    parent.spawn((
+                        Node {
+                            display: Display::None,
+                            align_self: AlignSelf::Center,
+                            margin: UiRect::horizontal(Val::Px(5.)),
+                            ..default()
+                        },
+                        Text::default(),
+                        utils::text_style(ThemeColor::Error),
+                        Label,
+                        SearchErrorMarker,
+                        Name::new("search_error"),
+                    ));
+
+                    parent.spawn((
+                        Node {
+                            display: Display::None,
+                            align_self: AlignSelf::Center,
+                            margin: UiRect::horizontal(Val::Px(5.)),
+                            ..default()
+                        },
+                        Text::default(),
+                        utils::text_style(ThemeColor::Warning),
+                        Label,
+                        DroppedLinesMarker,
+                        Name::new("dropped_lines"),
+                    ));
+
+                    parent.spawn((
+                        Node {
+                            display: Display::None,
+                            align_self: AlignSelf::Center,
+                            margin: UiRect::horizontal(Val::Px(5.)),
+                            ..default()
+                        },
+                        Text::default(),
+                        utils::text_style(ThemeColor::Warning),
+                        Label,
+                        LostLogsMarker,
+                        Name::new("lost_logs"),
                     ));
 
                     if let Some(level) = auto_open_level {
@@ -235,10 +526,134 @@ pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewer
                         ));
                     }
 
+                    parent.spawn((
+                        Node {
+                            display: Display::None,
+                            align_self: AlignSelf::Center,
+                            margin: UiRect::horizontal(Val::Px(5.)),
+                            ..default()
+                        },
+                        Text::default(),
+                        utils::text_style(ThemeColor::Text),
+                        Label,
+                        StatusTextMarker,
+                        Name::new("status_text"),
+                    ));
+                    #[cfg(feature = "clipboard")]
+                    parent.spawn(utils::toggle_chip(
+                        CopyButton,
+                        "Copy".into(),
+                        false,
+                        "copy_btn",
+                    ));
+                    parent.spawn(utils::toggle_chip(
+                        ExportButton,
+                        "Export".into(),
+                        false,
+                        "export_btn",
+                    ));
+
                     for (name, color, button) in traffic_lights {
                         parent.spawn(traffic_light(name, color, button));
                     }
-                });
+                })
+                .observe(on_title_bar_drag);
+
+            // Target panel, hidden until opened with the targets chip.
+            parent.spawn((
+                Node {
+                    display: Display::None,
+                    flex_direction: FlexDirection::Column,
+                    flex_shrink: 0.,
+                    max_height: Val::Percent(50.),
+                    padding: UiRect::vertical(Val::Px(2.)),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                TargetPanelMarker,
+                Name::new("target_panel"),
+                children![
+                    (
+                        Node {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        Name::new("target_directives"),
+                        children![
+                            (
+                                Node {
+                                    flex_grow: 1.,
+                                    max_width: Val::Px(300.),
+                                    ..default()
+                                },
+                                children![utils::text_input(
+                                    TargetDirectivesInputMarker,
+                                    "target_directives_input",
+                                    "Directives, e.g. game=debug,wgpu=off",
+                                )],
+                            ),
+                            (
+                                Node {
+                                    display: Display::None,
+                                    margin: UiRect::horizontal(Val::Px(5.)),
+                                    ..default()
+                                },
+                                Text::default(),
+                                utils::text_style(ThemeColor::Error),
+                                Label,
+                                TargetDirectivesErrorMarker,
+                                Name::new("target_directives_error"),
+                            ),
+                        ],
+                    ),
+                    (
+                        Node {
+                            display: Display::None,
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        LogFilterRowMarker,
+                        Name::new("log_filter"),
+                        children![
+                            (
+                                Node {
+                                    flex_grow: 1.,
+                                    max_width: Val::Px(300.),
+                                    ..default()
+                                },
+                                children![utils::text_input(
+                                    LogFilterInputMarker,
+                                    "log_filter_input",
+                                    "Log filter, e.g. info,game=trace",
+                                )],
+                            ),
+                            (
+                                Node {
+                                    display: Display::None,
+                                    margin: UiRect::horizontal(Val::Px(5.)),
+                                    ..default()
+                                },
+                                Text::default(),
+                                utils::text_style(ThemeColor::Error),
+                                Label,
+                                LogFilterErrorMarker,
+                                Name::new("log_filter_error"),
+                            ),
+                        ],
+                    ),
+                    (
+                        Node {
+                            flex_direction: FlexDirection::Row,
+                            flex_wrap: FlexWrap::Wrap,
+                            ..default()
+                        },
+                        TargetListMarker,
+                        Name::new("target_list"),
+                    ),
+                ],
+            ));
 
             // Button for scrolling to the bottom
             parent
@@ -263,8 +678,8 @@ pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewer
                     },
                     ZIndex(1),
                     Button,
-                    BorderColor::all(Color::WHITE),
-                    BackgroundColor(Color::BLACK.with_alpha(0.75)),
+                    ThemedBorder(ThemeColor::Text),
+                    ThemedBackground(ThemeColor::PanelBackground),
                     GoDownBtnMarker,
                     Name::new("go_down_btn"),
                     // A down-arrow icon: a square rotated 45 degrees, clipped at the top.
@@ -286,7 +701,7 @@ pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewer
                                 ..default()
                             },
                             UiTransform::from_rotation(Rot2::FRAC_PI_4),
-                            BackgroundColor(Color::WHITE),
+                            ThemedBackground(ThemeColor::Text),
                             Name::new("down_arrow"),
                         )],
                     )],
@@ -300,15 +715,16 @@ pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewer
                 .spawn((
                     Node {
                         height: Val::Percent(100.),
-                        overflow: Overflow::scroll_y(),
+                        overflow: Overflow::scroll(),
                         ..default()
                     },
                     Name::new("container"),
                     ListContainerMarker,
+                    // Sized and filled with pooled rows by `sync_log_rows`.
                     children![(
                         Node {
-                            flex_direction: FlexDirection::Column,
                             position_type: PositionType::Absolute,
+                            min_width: Val::Percent(100.),
                             ..default()
                         },
                         Pickable {
@@ -319,18 +735,152 @@ pub fn setup_log_viewer_ui(mut commands: Commands, log_viewer_res: Res<LogViewer
                         ListMarker,
                     )],
                 ))
-                .observe(on_drag_scroll);
+                .observe(on_drag_scroll)
+                .observe(
+                    |_: On<Pointer<Press>>, mut log_viewer_state: ResMut<LogViewerState>| {
+                        log_viewer_state.list_dragged = false;
+                    },
+                );
+
+            // Detail panel, covering the viewer while open.
+            parent
+                .spawn((
+                    Node {
+                        display: Display::None,
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(0.),
+                        bottom: Val::Px(0.),
+                        left: Val::Px(0.),
+                        right: Val::Px(0.),
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(4.)),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    ZIndex(2),
+                    ThemedBackground(ThemeColor::PanelBackground),
+                    DetailPanelMarker,
+                    Name::new("detail_panel"),
+                ))
+                .with_children(|parent| {
+                    // Built imperatively because the copy button is conditional.
+                    parent
+                        .spawn((
+                            Node {
+                                flex_direction: FlexDirection::Row,
+                                align_items: AlignItems::Center,
+                                flex_shrink: 0.,
+                                ..default()
+                            },
+                            Name::new("detail_title_bar"),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Node {
+                                    flex_grow: 1.,
+                                    ..default()
+                                },
+                                Text::new("Log details"),
+                                utils::text_style(ThemeColor::Text),
+                                Label,
+                            ));
+                            #[cfg(feature = "clipboard")]
+                            parent.spawn(utils::toggle_chip(
+                                DetailCopyButton,
+                                "Copy".into(),
+                                false,
+                                "detail_copy_btn",
+                            ));
+                            parent.spawn(utils::toggle_chip(
+                                DetailCloseButton,
+                                "Close".into(),
+                                false,
+                                "detail_close_btn",
+                            ));
+                        });
+                    parent
+                        .spawn((
+                            Node {
+                                flex_grow: 1.,
+                                min_height: Val::Px(0.),
+                                margin: UiRect::top(Val::Px(4.)),
+                                overflow: Overflow::scroll_y(),
+                                ..default()
+                            },
+                            DetailScrollMarker,
+                            Name::new("detail_scroll"),
+                            children![(
+                                Text::default(),
+                                utils::text_style(ThemeColor::Text),
+                                TextLayout::linebreak(LineBreak::AnyCharacter),
+                                Label,
+                                DetailTextMarker,
+                                Name::new("detail_text"),
+                            )],
+                        ))
+                        .observe(on_drag_scroll);
+                });
+
+            // Resize handle, placed on the inner edge by `sync_viewer_layout`.
+            parent
+                .spawn((
+                    Node {
+                        display: Display::None,
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    ZIndex(3),
+                    ResizeHandleMarker,
+                    Name::new("resize_handle"),
+                    children![(
+                        Node {
+                            border_radius: BorderRadius::all(Val::Px(2.)),
+                            ..default()
+                        },
+                        ThemedBackground(ThemeColor::MutedText),
+                        Pickable::IGNORE,
+                        ResizeGripMarker,
+                        Name::new("resize_grip"),
+                    )],
+                ))
+                .observe(on_resize_handle_drag);
+
+            // Corners resizing the viewer while floating, placed by `sync_resize_corners`.
+            for (corner, name) in [
+                (ScreenCorner::TopLeft, "resize_corner_top_left"),
+                (ScreenCorner::TopRight, "resize_corner_top_right"),
+                (ScreenCorner::BottomLeft, "resize_corner_bottom_left"),
+                (ScreenCorner::BottomRight, "resize_corner_bottom_right"),
+            ] {
+                parent
+                    .spawn((
+                        Node {
+                            display: Display::None,
+                            ..default()
+                        },
+                        ZIndex(3),
+                        ResizeCorner(corner),
+                        Name::new(name),
+                    ))
+                    .observe(on_resize_corner_drag);
+            }
         });
 }
 
+#[allow(clippy::type_complexity)]
 fn on_drag_scroll(
     drag: On<Pointer<Drag>>,
     mut last_drag: Local<Option<(PointerId, Vec2)>>,
     mut scroll_positions: Query<
-        (&mut ScrollPosition, &ComputedNode, &Children),
-        With<ListContainerMarker>,
+        (
+            &mut ScrollPosition,
+            &ComputedNode,
+            &Children,
+            Has<ListContainerMarker>,
+        ),
+        Or<(With<ListContainerMarker>, With<DetailScrollMarker>)>,
     >,
-    content_query: Query<&ComputedNode, With<ListMarker>>,
+    content_query: Query<&ComputedNode, Or<(With<ListMarker>, With<DetailTextMarker>)>>,
     mut log_viewer_state: ResMut<LogViewerState>,
 ) {
     // Pressing over a log line puts the line, the list and the container all in the pointer's
@@ -341,13 +891,17 @@ fn on_drag_scroll(
         return;
     }
 
-    if let Ok((mut scroll_position, container, children)) =
+    if let Ok((mut scroll_position, container, children, list)) =
         scroll_positions.get_mut(drag.event().entity)
     {
         if let Ok(content) = content_query.get(children[0]) {
             let max = utils::max_scroll(container, content);
+            scroll_position.x = (scroll_position.x - drag.delta.x).clamp(0., max.x);
             scroll_position.y = (scroll_position.y - drag.delta.y).clamp(0., max.y);
-            log_viewer_state.scroll_state = ScrollState::Manual;
+            if list {
+                log_viewer_state.scroll_state = ScrollState::Manual;
+                log_viewer_state.list_dragged = true;
+            }
         }
     }
 }
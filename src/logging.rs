@@ -1,86 +1,546 @@
 use crate::{
-    debug_log_level::DebugLogLevel,
+    dock::{
+        sync_resize_corners, sync_viewer_layout, DEFAULT_BIG_SIZE, DEFAULT_FLOATING_RECT,
+        DEFAULT_SMALL_SIZE, MIN_SIZE,
+    },
+    export::{handle_export_logs, hide_status, on_export_button},
+    gesture::detect_toggle_gesture,
+    global_filter::{
+        handle_set_log_filter, on_log_filter_input, sync_log_filter_input, with_reloadable_filter,
+    },
+    keybindings::handle_key_bindings,
+    log_detail::{on_detail_close_button, sync_detail_panel},
+    log_file::{read_previous_session, LogFileSink, RotatingFile},
+    log_filter::{LogTargets, SearchFilter, TargetDirectives},
+    log_list::{self, sync_log_rows, FilteredLogs},
+    log_queue::{LogQueue, OverflowPolicy, DEFAULT_CHANNEL_CAPACITY},
     log_viewer::{
-        setup_log_viewer_ui, AutoCheckBox, ChipToggle, GoDownBtnMarker, LevelFilterChip,
-        ListContainerMarker, ListMarker, LogViewerMarker, LogViewerState, ScrollState,
-        TrafficLightButton, RENDER_LAYER,
+        setup_log_viewer_ui, AutoCheckBox, ChipToggle, CollapseChip, DetailScrollMarker,
+        DetailTextMarker, DroppedLinesMarker, GoDownBtnMarker, LevelFilterChip,
+        ListContainerMarker, ListMarker, LogViewerMarker, LogViewerState, LostLogsMarker,
+        RegexChip, ScrollState, SearchErrorMarker, SearchInputMarker, TargetDirectivesErrorMarker,
+        TargetDirectivesInputMarker, TargetListMarker, TargetPanelMarker, TargetToggle,
+        TargetsChip, TrafficLightButton, RENDER_LAYER,
     },
-    utils::{CheckboxIconMarker, ChipLeadingTextMarker},
+    rate_limit::RateLimiter,
+    theme::{apply_theme, sync_level_chips, ThemeColor, ThemedText},
+    utils::{
+        self, CheckboxIconMarker, ChipLeadingTextMarker, TextInput, TextInputTextMarker, ToggleChip,
+    },
+    zoom::{self, pinch_zoom},
+    LogFilter, LogFontSize, LogStore, LogViewerDock, LogViewerKeyBindings, LogViewerTheme,
+    ToggleGesture, DEFAULT_LOG_CAPACITY,
 };
 use bevy_app::prelude::*;
-use bevy_camera::{prelude::*, visibility::RenderLayers};
-use bevy_color::{palettes::css, prelude::*};
-use bevy_derive::{Deref, DerefMut};
+use bevy_camera::{prelude::*, visibility::RenderLayers, RenderTarget};
+use bevy_color::prelude::*;
+use bevy_derive::Deref;
 use bevy_ecs::prelude::*;
-use bevy_input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy_input::{
+    keyboard::{Key, KeyCode, KeyboardInput},
+    mouse::{MouseButton, MouseScrollUnit, MouseWheel},
+    touch::Touches,
+    ButtonInput,
+};
 use bevy_log::{
-    tracing::{self, level_filters::LevelFilter, Subscriber},
-    tracing_subscriber::{self, Layer},
-    BoxedLayer,
+    tracing::{self, level_filters::LevelFilter, span, Subscriber},
+    tracing_subscriber::{layer::Context, registry::LookupSpan, Layer},
+    warn, BoxedLayer, Level,
 };
-use bevy_picking::{hover::HoverMap, prelude::*};
+use bevy_math::Rect;
+use bevy_picking::{hover::HoverMap, pointer::PointerId};
 use bevy_render::prelude::*;
-use bevy_text::prelude::*;
+use bevy_text::FontSource;
 use bevy_ui::prelude::*;
 use bevy_utils::prelude::*;
-use std::{num::NonZero, sync::mpsc};
-use time::{format_description::well_known::iso8601, OffsetDateTime};
+use bevy_window::{
+    close_when_requested, ClosingWindow, ExitSystems, Window, WindowCloseRequested, WindowClosed,
+    WindowRef,
+};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use time::OffsetDateTime;
+
+/// A captured log record, read from the [`LogStore`].
+#[derive(Debug, Clone)]
+pub struct LogEvent {
+    /// The `message` field, missing for events that only carry structured data.
+    pub(crate) message: Option<String>,
+    /// All recorded fields other than `message`, in the order they were recorded.
+    pub(crate) fields: Vec<(Cow<'static, str>, FieldValue)>,
+    /// The spans the event was emitted in, outermost first.
+    pub(crate) spans: Vec<SpanContext>,
+    pub(crate) level: Level,
+    pub(crate) target: Cow<'static, str>,
+    pub(crate) module_path: Option<Cow<'static, str>>,
+    pub(crate) file: Option<Cow<'static, str>>,
+    pub(crate) line: Option<u32>,
+    pub(crate) timestamp: OffsetDateTime,
+    /// Whether the record was loaded from the previous session's log file.
+    pub(crate) previous_session: bool,
+}
+
+impl LogEvent {
+    /// A record of the given callsite without message, fields or spans.
+    fn new(metadata: &'static tracing::Metadata<'static>, timestamp: OffsetDateTime) -> Self {
+        Self {
+            message: None,
+            fields: Vec::new(),
+            spans: Vec::new(),
+            level: *metadata.level(),
+            target: metadata.target().into(),
+            module_path: metadata.module_path().map(Into::into),
+            file: metadata.file().map(Into::into),
+            line: metadata.line(),
+            timestamp,
+            previous_session: false,
+        }
+    }
+
+    /// A record of `level` and `target` with only a message, for tests.
+    #[cfg(test)]
+    pub(crate) fn test(level: Level, target: &'static str, message: &str) -> Self {
+        Self {
+            message: Some(message.to_string()),
+            fields: Vec::new(),
+            spans: Vec::new(),
+            level,
+            target: target.into(),
+            module_path: None,
+            file: None,
+            line: None,
+            timestamp: OffsetDateTime::UNIX_EPOCH,
+            previous_session: false,
+        }
+    }
+
+    /// The `message` field, missing for events that only carry structured data.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// All recorded fields other than `message` as name and value, in the order they were
+    /// recorded.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &FieldValue)> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.as_ref(), value))
+    }
+
+    /// The spans the event was emitted in, outermost first.
+    pub fn spans(&self) -> &[SpanContext] {
+        &self.spans
+    }
+
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn module_path(&self) -> Option<&str> {
+        self.module_path.as_deref()
+    }
+
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    pub fn timestamp(&self) -> OffsetDateTime {
+        self.timestamp
+    }
+
+    /// Whether the record was loaded from the previous session's log file.
+    pub fn previous_session(&self) -> bool {
+        self.previous_session
+    }
+
+    /// The fields formatted as space separated `key=value` pairs.
+    pub fn fields_text(&self) -> String {
+        format_fields(&self.fields)
+    }
+
+    /// Whether `other` repeats this record, ignoring when and in which spans it was logged, but
+    /// not across sessions.
+    pub(crate) fn repeats(&self, other: &LogEvent) -> bool {
+        self.previous_session == other.previous_session
+            && self.level == other.level
+            && self.target == other.target
+            && self.message == other.message
+            && self.fields == other.fields
+    }
+
+    /// The span stack formatted as a breadcrumb, e.g. `level_load{id=3} > spawn_enemies`.
+    pub fn spans_text(&self) -> String {
+        self.spans
+            .iter()
+            .map(|span| {
+                if span.fields.is_empty() {
+                    span.name.to_string()
+                } else {
+                    format!("{}{{{}}}", span.name, format_fields(&span.fields))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" > ")
+    }
+}
+
+fn format_fields(fields: &[(Cow<'static, str>, FieldValue)]) -> String {
+    fields
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The value of a captured field, keeping the type it was recorded with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    /// A string, shown quoted like `tracing_subscriber::fmt` does.
+    Str(String),
+    /// Any other value, e.g. recorded with `?` or `%`, as it was formatted.
+    Text(String),
+}
+
+impl FieldValue {
+    /// The value as JSON, with strings and formatted values unquoted.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        match self {
+            FieldValue::Bool(value) => (*value).into(),
+            FieldValue::I64(value) => (*value).into(),
+            FieldValue::U64(value) => (*value).into(),
+            // JSON has no NaN or infinity.
+            FieldValue::F64(value) => serde_json::Number::from_f64(*value)
+                .map_or_else(|| value.to_string().into(), serde_json::Value::Number),
+            FieldValue::Str(value) | FieldValue::Text(value) => value.as_str().into(),
+        }
+    }
+
+    /// Reads a value written by [`FieldValue::to_json`]; strings are read back as [`FieldValue::Str`].
+    pub(crate) fn from_json(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Bool(value) => FieldValue::Bool(*value),
+            serde_json::Value::Number(number) => number
+                .as_i64()
+                .map(FieldValue::I64)
+                .or_else(|| number.as_u64().map(FieldValue::U64))
+                .or_else(|| number.as_f64().map(FieldValue::F64))
+                .unwrap_or_else(|| FieldValue::Text(number.to_string())),
+            serde_json::Value::String(value) => FieldValue::Str(value.clone()),
+            value => FieldValue::Text(value.to_string()),
+        }
+    }
+
+    /// The value as message text, strings unquoted.
+    fn into_text(self) -> String {
+        match self {
+            FieldValue::Str(value) | FieldValue::Text(value) => value,
+            value => value.to_string(),
+        }
+    }
+}
 
-const LOG_LINE_FONT_SIZE: f32 = 8.;
+impl std::fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldValue::Bool(value) => value.fmt(f),
+            FieldValue::I64(value) => value.fmt(f),
+            FieldValue::U64(value) => value.fmt(f),
+            FieldValue::F64(value) => value.fmt(f),
+            FieldValue::Str(value) => write!(f, "{value:?}"),
+            FieldValue::Text(value) => f.write_str(value),
+        }
+    }
+}
 
-#[derive(Debug, Message, Clone)]
-struct LogEvent {
-    message: String,
-    metadata: &'static tracing::Metadata<'static>,
-    timestamp: OffsetDateTime,
+/// Name and fields of a span, stored in the span's extensions by [`LogCaptureLayer`].
+#[derive(Debug, Clone)]
+pub struct SpanContext {
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) fields: Vec<(Cow<'static, str>, FieldValue)>,
+}
+
+impl SpanContext {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The span's fields as name and value.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &FieldValue)> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.as_ref(), value))
+    }
+
+    /// Records the given values, replacing earlier values of the same fields.
+    fn record(&mut self, values: &span::Record<'_>) {
+        for (name, value) in record_span_fields(values) {
+            match self
+                .fields
+                .iter_mut()
+                .find(|(existing, _)| *existing == name)
+            {
+                Some((_, existing)) => *existing = value,
+                None => self.fields.push((name, value)),
+            }
+        }
+    }
+}
+
+fn record_span_fields(values: &span::Record<'_>) -> Vec<(Cow<'static, str>, FieldValue)> {
+    let mut message = None;
+    let mut fields = Vec::new();
+    values.record(&mut CaptureLayerVisitor {
+        message: &mut message,
+        fields: &mut fields,
+        capture_fields: true,
+    });
+    // Spans rarely have a message, but if they do it is just another field.
+    if let Some(message) = message {
+        fields.insert(0, ("message".into(), FieldValue::Text(message)));
+    }
+    fields
 }
 
 #[derive(Debug, Event, Clone)]
 pub(crate) struct ScrollToBottom;
 
-#[derive(Deref, DerefMut)]
-struct LogEventsReceiver(mpsc::Receiver<LogEvent>);
+#[derive(Resource, Deref)]
+struct LogEventsReceiver(Arc<LogQueue>);
 
-struct CaptureLayer {
-    sender: mpsc::Sender<LogEvent>,
+/// The log file the capture layer writes to, shared to report its failures.
+#[derive(Resource, Deref)]
+struct LogFileWriter(Arc<Mutex<RotatingFile>>);
+
+/// Logs the first failure to write the log file, which the capture layer itself can't.
+fn report_log_file_error(file: Res<LogFileWriter>) {
+    // Not logging while holding the lock, the capture layer takes it for every log.
+    let failure = file
+        .lock()
+        .ok()
+        .and_then(|mut file| file.take_error().map(|error| (file.path(), error)));
+    if let Some((path, error)) = failure {
+        warn!(
+            "failed to write log file {}, later logs may be missing from it: {error}",
+            path.display()
+        );
+    }
 }
-impl<S: Subscriber> Layer<S> for CaptureLayer {
-    fn on_event(
-        &self,
-        event: &tracing::Event<'_>,
-        _ctx: tracing_subscriber::layer::Context<'_, S>,
-    ) {
-        // In order to obtain the log message, we have to create a struct that implements
-        // Visit and holds a reference to our string. Then we use the `record` method and
-        // the struct to modify the reference to hold the message string.
+
+/// The [`Layer`] capturing logs for the viewer, created by [`LogCaptureLayer::builder`] or
+/// [`log_capture_layer`].
+pub struct LogCaptureLayer {
+    queue: Arc<LogQueue>,
+    file: Option<Arc<Mutex<RotatingFile>>>,
+    min_level: LevelFilter,
+    targets: Option<TargetDirectives>,
+    capture_fields: bool,
+    capture_spans: bool,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl LogCaptureLayer {
+    pub fn builder() -> LogCaptureLayerBuilder {
+        LogCaptureLayerBuilder::default()
+    }
+}
+
+impl<S> Layer<S> for LogCaptureLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if !self.capture_spans {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            let mut context = SpanContext {
+                name: attrs.metadata().name().into(),
+                fields: Vec::new(),
+            };
+            context.record(&span::Record::new(attrs.values()));
+            span.extensions_mut().insert(context);
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if !self.capture_spans {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            if let Some(context) = span.extensions_mut().get_mut::<SpanContext>() {
+                context.record(values);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        // Filtering here rather than in `enabled`, which would disable events for all layers.
+        let metadata = event.metadata();
+        if *metadata.level() > self.min_level
+            || self
+                .targets
+                .as_ref()
+                .is_some_and(|targets| !targets.enabled(metadata.target(), *metadata.level()))
+        {
+            return;
+        }
+
+        let now = OffsetDateTime::now_utc();
+        if let Some(rate_limiter) = &self.rate_limiter {
+            let (allowed, suppressed) = rate_limiter.check(metadata, now);
+            for (metadata, count) in suppressed {
+                self.capture(LogEvent {
+                    message: Some(format!("{count} logs suppressed by the rate limit")),
+                    ..LogEvent::new(metadata, now)
+                });
+            }
+            if !allowed {
+                return;
+            }
+        }
+
+        // In order to obtain the log message and fields, we have to create a struct that
+        // implements Visit and holds references to our message and field list. Then we use the
+        // `record` method and the struct to fill them in.
         let mut message = None;
-        event.record(&mut CaptureLayerVisitor(&mut message));
-        if let Some(message) = message {
-            self.sender
-                .send(LogEvent {
-                    message,
-                    metadata: event.metadata(),
-                    timestamp: OffsetDateTime::now_utc(),
-                })
-                .ok();
+        let mut fields = Vec::new();
+        event.record(&mut CaptureLayerVisitor {
+            message: &mut message,
+            fields: &mut fields,
+            capture_fields: self.capture_fields,
+        });
+        let spans = ctx
+            .event_scope(event)
+            .filter(|_| self.capture_spans)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .filter_map(|span| span.extensions().get::<SpanContext>().cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        self.capture(LogEvent {
+            message,
+            fields,
+            spans,
+            ..LogEvent::new(metadata, now)
+        });
+    }
+}
+
+impl LogCaptureLayer {
+    fn capture(&self, record: LogEvent) {
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                file.write(&record);
+            }
+        }
+
+        self.queue.push(record);
+    }
+}
+
+/// A [`Visit`](tracing::field::Visit)or that records log messages and fields that are transferred to [`LogCaptureLayer`].
+struct CaptureLayerVisitor<'a> {
+    message: &'a mut Option<String>,
+    fields: &'a mut Vec<(Cow<'static, str>, FieldValue)>,
+    /// Whether fields other than `message` are recorded.
+    capture_fields: bool,
+}
+
+impl CaptureLayerVisitor<'_> {
+    fn record(&mut self, field: &tracing::field::Field, value: FieldValue) {
+        match field.name() {
+            "message" => *self.message = Some(value.into_text()),
+            // Metadata of events forwarded from the `log` crate, already part of `Metadata`.
+            name if name.starts_with("log.") || !self.capture_fields => {}
+            name => self.fields.push((name.into(), value)),
         }
     }
 }
 
-/// A [`Visit`](tracing::field::Visit)or that records log messages that are transferred to [`CaptureLayer`].
-struct CaptureLayerVisitor<'a>(&'a mut Option<String>);
 impl tracing::field::Visit for CaptureLayerVisitor<'_> {
+    fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
+        self.record(field, FieldValue::F64(value));
+    }
+
+    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
+        self.record(field, FieldValue::I64(value));
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.record(field, FieldValue::U64(value));
+    }
+
+    fn record_i128(&mut self, field: &tracing::field::Field, value: i128) {
+        let value = i64::try_from(value)
+            .map_or_else(|_| FieldValue::Text(value.to_string()), FieldValue::I64);
+        self.record(field, value);
+    }
+
+    fn record_u128(&mut self, field: &tracing::field::Field, value: u128) {
+        let value = u64::try_from(value)
+            .map_or_else(|_| FieldValue::Text(value.to_string()), FieldValue::U64);
+        self.record(field, value);
+    }
+
+    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
+        self.record(field, FieldValue::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.record(field, FieldValue::Str(value.to_string()));
+    }
+
+    fn record_error(
+        &mut self,
+        field: &tracing::field::Field,
+        value: &(dyn std::error::Error + 'static),
+    ) {
+        self.record(field, FieldValue::Text(value.to_string()));
+    }
+
     fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
-        // This if statement filters out events without a message field.
-        if field.name() == "message" {
-            *self.0 = Some(format!("{value:?}"));
-        }
+        self.record(field, FieldValue::Text(format!("{value:?}")));
     }
 }
 
 pub struct LogViewerPlugin {
     auto_open_threshold: LevelFilter,
     msaa: Msaa,
+    capacity: usize,
+    capture_without_message: bool,
+    target_filter: String,
+    log_filter: Option<String>,
+    export_dir: Option<PathBuf>,
+    previous_session: bool,
+    collapse_repeats: bool,
+    key_bindings: LogViewerKeyBindings,
+    toggle_gesture: Option<ToggleGesture>,
+    theme: LogViewerTheme,
+    log_font_size: LogFontSize,
+    dock: LogViewerDock,
+    small_size: f32,
+    big_size: f32,
+    floating_rect: Rect,
+    window: Option<Window>,
 }
 
 impl Default for LogViewerPlugin {
@@ -88,6 +548,22 @@ impl Default for LogViewerPlugin {
         Self {
             auto_open_threshold: LevelFilter::ERROR,
             msaa: Msaa::default(),
+            capacity: DEFAULT_LOG_CAPACITY,
+            capture_without_message: true,
+            target_filter: String::new(),
+            log_filter: None,
+            export_dir: None,
+            previous_session: true,
+            collapse_repeats: true,
+            key_bindings: LogViewerKeyBindings::default(),
+            toggle_gesture: None,
+            theme: LogViewerTheme::default(),
+            log_font_size: LogFontSize::default(),
+            dock: LogViewerDock::default(),
+            small_size: DEFAULT_SMALL_SIZE,
+            big_size: DEFAULT_BIG_SIZE,
+            floating_rect: DEFAULT_FLOATING_RECT,
+            window: None,
         }
     }
 }
@@ -101,15 +577,143 @@ impl LogViewerPlugin {
         self.msaa = msaa;
         self
     }
+    /// Maximum number of log lines kept in the [`LogStore`]; the oldest lines are dropped first.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+    /// Whether events without a `message` field, e.g. `info!(score = 10)`, are shown.
+    /// Their fields are used as the message. Enabled by default.
+    pub fn capture_without_message(mut self, capture: bool) -> Self {
+        self.capture_without_message = capture;
+        self
+    }
+    /// Initial target filter directives of the viewer, see [`LogViewerTargetFilter`].
+    pub fn target_filter(mut self, directives: impl Into<String>) -> Self {
+        self.target_filter = directives.into();
+        self
+    }
+    /// Initial directives of the global tracing filter installed by
+    /// [`reloadable_log_capture_layer`](crate::reloadable_log_capture_layer), see [`SetLogFilter`](crate::SetLogFilter).
+    pub fn log_filter(mut self, directives: impl Into<String>) -> Self {
+        self.log_filter = Some(directives.into());
+        self
+    }
+    /// Directory the export button writes to. Defaults to the system's temp dir, which is not
+    /// writable by apps on Android, so set the app's files dir there.
+    pub fn export_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.export_dir = Some(dir.into());
+        self
+    }
+    /// Whether the end of the previous session's logs is loaded from the [`LogFileSink`] on
+    /// startup, shown above this session's logs. Enabled by default.
+    pub fn previous_session(mut self, load: bool) -> Self {
+        self.previous_session = load;
+        self
+    }
+    /// Whether consecutive repeats of a line are collapsed into one row with a repeat count.
+    /// Can be toggled in the title bar. Enabled by default.
+    pub fn collapse_repeats(mut self, collapse: bool) -> Self {
+        self.collapse_repeats = collapse;
+        self
+    }
+    /// Keyboard shortcuts of the viewer, see [`LogViewerKeyBindings`] for the defaults. Pass
+    /// [`LogViewerKeyBindings::none`] to disable them.
+    pub fn key_bindings(mut self, key_bindings: LogViewerKeyBindings) -> Self {
+        self.key_bindings = key_bindings;
+        self
+    }
+    /// Touch gesture toggling the viewer, e.g. for mobile QA builds. None by default.
+    pub fn toggle_gesture(mut self, gesture: ToggleGesture) -> Self {
+        self.toggle_gesture = Some(gesture);
+        self
+    }
+    /// Colours, font and sizes of the viewer, [`LogViewerTheme::dark`] by default.
+    pub fn theme(mut self, theme: LogViewerTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+    /// Font of the viewer, e.g. a `Handle<Font>`, replacing the theme's. Required without the
+    /// `default_font` feature.
+    pub fn font(mut self, font: impl Into<FontSource>) -> Self {
+        self.theme.font = font.into();
+        self
+    }
+    /// Initial size of the log lines in logical px, 8 by default. Pinching or Ctrl+scrolling the
+    /// list changes the [`LogFontSize`] resource at runtime.
+    pub fn log_font_size(mut self, size: f32) -> Self {
+        self.log_font_size = LogFontSize(size);
+        self
+    }
+    /// Edge of the screen the viewer is docked to, [`LogViewerDock::Top`] by default.
+    pub fn dock(mut self, dock: LogViewerDock) -> Self {
+        self.dock = dock;
+        self
+    }
+    /// Share of the screen in percent the viewer covers while small, 40 by default, clamped to
+    /// 10 to 100. Dragging the viewer's inner edge resizes it at runtime.
+    pub fn small_size(mut self, percent: f32) -> Self {
+        self.small_size = percent.clamp(MIN_SIZE, 100.);
+        self
+    }
+    /// Share of the screen in percent the viewer covers while big, 100 by default, clamped to 10
+    /// to 100. A big size below the small size is raised to it.
+    pub fn big_size(mut self, percent: f32) -> Self {
+        self.big_size = percent.clamp(MIN_SIZE, 100.);
+        self
+    }
+    /// Initial position and size in logical px of the viewer while floating, see
+    /// [`LogViewerSize::Floating`]. The viewer remembers where it was moved to while running.
+    pub fn floating_rect(mut self, rect: Rect) -> Self {
+        self.floating_rect = rect;
+        self
+    }
+    /// Shows the viewer in its own OS window instead of over the game, e.g.
+    /// `Window { title: "Logs".into(), ..default() }`. The viewer fills the window, ignoring the
+    /// dock and sizes, and the window is shown and hidden together with the viewer; closing it
+    /// hides the viewer. Closing the last game window closes it too, so the app exits as usual.
+    /// Desktop only.
+    pub fn window(mut self, window: Window) -> Self {
+        self.window = Some(window);
+        self
+    }
 }
 
 impl Plugin for LogViewerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_message::<LogEvent>();
+        app.insert_resource(LogStore::new(self.capacity));
+        app.init_resource::<FilteredLogs>();
+        app.init_resource::<LogTargets>();
+        app.insert_resource(self.key_bindings.clone());
+        app.insert_resource(self.theme.clone());
+        app.insert_resource(self.log_font_size);
+        if let Some(gesture) = self.toggle_gesture {
+            app.insert_resource(gesture);
+        }
 
+        // In its own window the viewer fills the window, wherever it would be docked.
+        let (dock, small_size, big_size) = if self.window.is_some() {
+            (LogViewerDock::Top, 100., 100.)
+        } else {
+            (
+                self.dock,
+                self.small_size,
+                self.big_size.max(self.small_size),
+            )
+        };
         app.insert_resource(LogViewerState {
             auto_open_threshold: self.auto_open_threshold,
             auto_open_enabled: self.auto_open_threshold != LevelFilter::OFF,
+            capture_without_message: self.capture_without_message,
+            export_dir: self.export_dir.clone(),
+            collapse_repeats: self.collapse_repeats,
+            dock,
+            small_size,
+            big_size,
+            size: small_size,
+            floating: dock == LogViewerDock::Floating,
+            floating_rect: self.floating_rect,
+            own_window: self.window.is_some(),
             ..default()
         });
         app.add_observer(handle_log_viewer_visibilty);
@@ -118,56 +722,335 @@ impl Plugin for LogViewerPlugin {
         app.add_observer(handle_auto_open_check);
         app.add_observer(handle_level_filter_chip_toggle);
         app.add_observer(handle_scroll_to_bottom);
+        app.add_observer(handle_log_viewer_search);
+        app.add_observer(handle_log_viewer_target_filter);
+        app.add_observer(handle_set_log_filter);
+        app.add_observer(handle_export_logs);
+
+        #[cfg(feature = "clipboard")]
+        {
+            // Keeps a clipboard the app already set up.
+            app.init_resource::<bevy_clipboard::Clipboard>();
+            app.add_systems(
+                Update,
+                (
+                    crate::clipboard::on_copy_button,
+                    crate::clipboard::on_detail_copy_button,
+                ),
+            );
+        }
+
+        if let Some(directives) = &self.log_filter {
+            match app.world_mut().get_resource_mut::<LogFilter>() {
+                Some(mut log_filter) => {
+                    if let Err(error) = log_filter.set(directives) {
+                        warn!("invalid log filter `{directives}`: {error}");
+                    }
+                }
+                None => warn!(
+                    "`LogViewerPlugin::log_filter` requires `reloadable_log_capture_layer` to be installed"
+                ),
+            }
+        }
 
         app.add_systems(Startup, setup_log_viewer_ui);
+        if self.previous_session {
+            app.add_systems(Startup, load_previous_session);
+        }
+        if !self.target_filter.is_empty() {
+            let directives = self.target_filter.clone();
+            app.add_systems(
+                Startup,
+                (move |mut commands: Commands| {
+                    commands.trigger(LogViewerTargetFilter(directives.clone()));
+                })
+                .after(setup_log_viewer_ui),
+            );
+        }
 
         // TODO: remove once https://github.com/bevyengine/bevy/issues/16590 is fixed
         let msaa = self.msaa;
-        app.add_systems(Startup, move |mut commands: Commands| {
-            commands.spawn((
-                Camera2d,
-                Camera {
-                    order: 1,
-                    clear_color: ClearColorConfig::None,
-                    ..default()
-                },
-                RenderLayers::layer(RENDER_LAYER),
-                LogViewerMarker,
-                msaa,
-            ));
-        });
+        let window = self.window.clone();
+        app.add_systems(
+            Startup,
+            (move |mut commands: Commands,
+                   viewer_query: Query<Entity, (With<LogViewerMarker>, With<Node>)>| {
+                // Hidden like the viewer until it is shown.
+                let target = window.as_ref().map(|window| {
+                    commands
+                        .spawn((
+                            Window {
+                                visible: false,
+                                ..window.clone()
+                            },
+                            LogViewerMarker,
+                        ))
+                        .id()
+                });
+                let camera = commands
+                    .spawn((
+                        Camera2d,
+                        Camera {
+                            order: 1,
+                            clear_color: if target.is_some() {
+                                ClearColorConfig::Default
+                            } else {
+                                ClearColorConfig::None
+                            },
+                            ..default()
+                        },
+                        RenderLayers::layer(RENDER_LAYER),
+                        LogViewerMarker,
+                        msaa,
+                    ))
+                    .id();
+                if let Some(target) = target {
+                    commands
+                        .entity(camera)
+                        .insert(RenderTarget::Window(WindowRef::Entity(target)));
+                    for viewer in viewer_query.iter() {
+                        commands.entity(viewer).insert(UiTargetCamera(camera));
+                    }
+                }
+            })
+            .after(setup_log_viewer_ui),
+        );
+        if self.window.is_some() {
+            app.add_systems(
+                Last,
+                (
+                    hide_on_window_close.after(close_when_requested),
+                    close_with_game_windows.before(ExitSystems),
+                ),
+            );
+        }
 
         // Running update_log_ui in PreUpdate to prevent data races between updating the UI and filtering log lines.
         // `handle_level_filter_chip_toggle`` can modify the `{level}_visible` fields in `LogViewerState`
         // while `update_log_ui` is adding new loglines to the viewer in parallel based on older values.
-        app.add_systems(PreUpdate, (receive_logs, update_log_counts).chain());
+        app.add_systems(
+            PreUpdate,
+            (receive_logs, update_log_counts, update_dropped_lines).chain(),
+        );
 
         app.add_systems(
             Update,
             (
+                handle_key_bindings,
+                detect_toggle_gesture,
+                sync_viewer_layout,
+                sync_resize_corners,
+                pinch_zoom,
                 on_traffic_light_button,
+                (on_export_button, hide_status),
+                (on_detail_close_button, sync_detail_panel).chain(),
                 on_auto_open_check,
                 on_level_filter_chip,
+                (
+                    on_text_input_focus,
+                    on_text_input_keyboard,
+                    update_text_input,
+                )
+                    .chain(),
+                on_search_input,
+                on_regex_chip,
+                on_collapse_chip,
+                (
+                    on_targets_chip,
+                    on_target_toggle,
+                    on_target_directives_input,
+                ),
+                sync_target_panel,
+                (on_log_filter_input, sync_log_filter_input),
                 (
                     manage_scroll_ui_state,
                     handle_listcontainer_overflow,
                     handle_scroll_update,
+                    sync_log_rows,
                 )
                     .chain(),
+                (sync_level_chips, apply_theme).after(update_text_input),
             ),
         );
     }
 }
 
+/// The default capture layer for `LogPlugin::custom_layer`, capturing everything that passes
+/// `LogPlugin`'s filter. Use [`LogCaptureLayer::builder`] to configure the capture.
 pub fn log_capture_layer(app: &mut App) -> Option<BoxedLayer> {
-    let (sender, receiver) = mpsc::channel();
+    LogCaptureLayer::builder().build(app)
+}
 
-    let layer = CaptureLayer { sender };
-    let log_receiver = LogEventsReceiver(receiver);
+/// Like [`log_capture_layer`], but also writes all logs to a rotating file, so they survive a
+/// crash of the app. Use it in a closure, e.g.
+/// `custom_layer: |app| log_capture_layer_with_file(app, LogFileSink::new("logs"))`.
+///
+/// If the log file can't be created, logs are only captured for the viewer.
+pub fn log_capture_layer_with_file(app: &mut App, sink: LogFileSink) -> Option<BoxedLayer> {
+    LogCaptureLayer::builder().file(sink).build(app)
+}
 
-    app.insert_non_send(log_receiver);
+/// Configures a [`LogCaptureLayer`].
+///
+/// `LogPlugin::custom_layer` takes a function pointer, so build the layer in a closure that
+/// doesn't capture anything:
+///
+/// ```ignore
+/// LogPlugin {
+///     custom_layer: |app| {
+///         LogCaptureLayer::builder()
+///             .min_level(LevelFilter::DEBUG)
+///             .targets("wgpu=off")
+///             .build(app)
+///     },
+///     ..default()
+/// }
+/// ```
+///
+/// The capture filters apply on top of `LogPlugin`'s filter and only affect what reaches the
+/// viewer and the log file, not the terminal.
+#[derive(Debug, Clone)]
+pub struct LogCaptureLayerBuilder {
+    min_level: LevelFilter,
+    targets: Option<String>,
+    capture_fields: bool,
+    capture_spans: bool,
+    channel_capacity: usize,
+    overflow_policy: OverflowPolicy,
+    rate_limit: Option<u32>,
+    file: Option<LogFileSink>,
+    reloadable_filter: bool,
+}
 
-    Some(layer.boxed())
+impl Default for LogCaptureLayerBuilder {
+    fn default() -> Self {
+        Self {
+            min_level: LevelFilter::TRACE,
+            targets: None,
+            capture_fields: true,
+            capture_spans: true,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+            rate_limit: None,
+            file: None,
+            reloadable_filter: false,
+        }
+    }
+}
+
+impl LogCaptureLayerBuilder {
+    /// Least severe level that is captured.
+    pub fn min_level(mut self, level: LevelFilter) -> Self {
+        self.min_level = level;
+        self
+    }
+    /// `EnvFilter`-style directives of the captured targets, e.g. `game=debug,wgpu=off`.
+    pub fn targets(mut self, directives: impl Into<String>) -> Self {
+        self.targets = Some(directives.into());
+        self
+    }
+    /// Whether structured fields are captured. Enabled by default.
+    pub fn capture_fields(mut self, capture: bool) -> Self {
+        self.capture_fields = capture;
+        self
+    }
+    /// Whether the spans a log was emitted in are captured. Enabled by default.
+    pub fn capture_spans(mut self, capture: bool) -> Self {
+        self.capture_spans = capture;
+        self
+    }
+    /// Number of logs buffered until the viewer receives them on the next frame, so a log storm
+    /// on another thread can't grow memory without limit. [`DEFAULT_CHANNEL_CAPACITY`] by
+    /// default.
+    ///
+    /// While the buffer is full, logs are dropped according to [`Self::overflow_policy`] and
+    /// counted in [`LogStore::lost`].
+    pub fn channel_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = capacity.max(1);
+        self
+    }
+    /// Which logs to drop while the buffer is full, the newest by default.
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+    /// Captures at most `per_second` logs a second from any single callsite, e.g. one `warn!` in
+    /// a hot loop, allowing bursts of as many. Suppressed logs are reported in a summary line
+    /// once the callsite is allowed to log again. Unlimited by default.
+    ///
+    /// Logs forwarded from the `log` crate share one callsite per level. A limit of 0 is raised
+    /// to 1.
+    pub fn rate_limit(mut self, per_second: u32) -> Self {
+        self.rate_limit = Some(per_second);
+        self
+    }
+    /// Also writes all captured logs to a rotating file, see [`log_capture_layer_with_file`].
+    pub fn file(mut self, sink: LogFileSink) -> Self {
+        self.file = Some(sink);
+        self
+    }
+    /// Also installs a global tracing filter that can be changed at runtime, see
+    /// [`reloadable_log_capture_layer`](crate::reloadable_log_capture_layer).
+    pub fn reloadable_filter(mut self, reloadable: bool) -> Self {
+        self.reloadable_filter = reloadable;
+        self
+    }
+
+    pub fn build(self, app: &mut App) -> Option<BoxedLayer> {
+        // The logger isn't set up yet at this point, so problems are logged once it is.
+        let mut errors = Vec::new();
+        let targets =
+            self.targets
+                .and_then(|directives| match directives.parse::<TargetDirectives>() {
+                    Ok(targets) => Some(targets),
+                    Err(error) => {
+                        errors.push(format!("invalid capture targets `{directives}`: {error}"));
+                        None
+                    }
+                });
+
+        let file = self.file.and_then(|sink| {
+            let file = RotatingFile::open(sink.clone());
+            if let Err(error) = &file {
+                errors.push(format!(
+                    "failed to open log file {}: {error}",
+                    sink.path(0).display()
+                ));
+            }
+            app.insert_resource(sink);
+            file.ok().map(|file| Arc::new(Mutex::new(file)))
+        });
+        if let Some(file) = &file {
+            app.insert_resource(LogFileWriter(file.clone()));
+            app.add_systems(Update, report_log_file_error);
+        }
+        if !errors.is_empty() {
+            app.add_systems(Startup, move || {
+                for error in &errors {
+                    warn!("{error}");
+                }
+            });
+        }
+
+        let queue = Arc::new(LogQueue::new(self.channel_capacity, self.overflow_policy));
+
+        let layer = LogCaptureLayer {
+            queue: queue.clone(),
+            file,
+            min_level: self.min_level,
+            targets,
+            capture_fields: self.capture_fields,
+            capture_spans: self.capture_spans,
+            rate_limiter: self.rate_limit.map(RateLimiter::new),
+        };
+        app.insert_resource(LogEventsReceiver(queue));
+
+        if self.reloadable_filter {
+            Some(with_reloadable_filter(app, layer.boxed()))
+        } else {
+            Some(layer.boxed())
+        }
+    }
 }
 
 #[derive(Event, Debug, Clone, Copy)]
@@ -182,6 +1065,9 @@ pub enum LogViewerSize {
     Big,
     Small,
     Toggle,
+    /// Floats the viewer over the game at its last position and size, moved by dragging its
+    /// title bar and resized by dragging its corners. `Small` docks it again.
+    Floating,
 }
 
 #[derive(Event, Debug, Clone, Copy)]
@@ -190,67 +1076,45 @@ pub struct ClearLogs;
 #[derive(Event, Debug, Clone, Copy)]
 pub struct AutoOpenToggle;
 
-#[derive(Component)]
-struct LogLineMarker;
-
-#[derive(Component)]
-struct ErrLogLineMarker;
-
-type WithOnlyErrLogLine = (
-    With<ErrLogLineMarker>,
-    Without<WarnLogLineMarker>,
-    Without<InfoLogLineMarker>,
-    Without<DebugLogLineMarker>,
-    Without<TraceLogLineMarker>,
-);
-
-#[derive(Component)]
-struct WarnLogLineMarker;
-
-type WithOnlyWarnLogLine = (
-    Without<ErrLogLineMarker>,
-    With<WarnLogLineMarker>,
-    Without<InfoLogLineMarker>,
-    Without<DebugLogLineMarker>,
-    Without<TraceLogLineMarker>,
-);
-
-#[derive(Component)]
-struct InfoLogLineMarker;
-
-type WithOnlyInfoLogLine = (
-    Without<ErrLogLineMarker>,
-    Without<WarnLogLineMarker>,
-    With<InfoLogLineMarker>,
-    Without<DebugLogLineMarker>,
-    Without<TraceLogLineMarker>,
-);
-
-#[derive(Component)]
-struct DebugLogLineMarker;
-
-type WithOnlyDebugLogLine = (
-    Without<ErrLogLineMarker>,
-    Without<WarnLogLineMarker>,
-    Without<InfoLogLineMarker>,
-    With<DebugLogLineMarker>,
-    Without<TraceLogLineMarker>,
-);
-
-#[derive(Component)]
-struct TraceLogLineMarker;
-
-type WithOnlyTraceLogLine = (
-    Without<ErrLogLineMarker>,
-    Without<WarnLogLineMarker>,
-    Without<InfoLogLineMarker>,
-    Without<DebugLogLineMarker>,
-    With<TraceLogLineMarker>,
-);
+/// Sets the target filter of the viewer from `EnvFilter`-style directives, e.g.
+/// `game=debug,wgpu=off`, replacing whatever was typed into the target panel.
+///
+/// This only affects which lines the viewer shows, not what gets logged. An empty string shows
+/// all targets.
+#[derive(Event, Debug, Clone, Default)]
+pub struct LogViewerTargetFilter(pub String);
+
+/// Sets the search filter of the viewer, replacing whatever was typed into the search box.
+///
+/// With `regex` set, `query` is a [regex](https://docs.rs/regex) matched against each line as
+/// `target: message key=value`; otherwise lines containing `query` in their message, target or
+/// fields are shown, ignoring case. An empty query shows all lines.
+#[derive(Event, Debug, Clone, Default)]
+pub struct LogViewerSearch {
+    pub query: String,
+    pub regex: bool,
+}
+
+impl LogViewerSearch {
+    pub fn text(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            regex: false,
+        }
+    }
+
+    pub fn regex(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            regex: true,
+        }
+    }
+}
 
 fn handle_log_viewer_visibilty(
     trigger: On<LogViewerVisibility>,
     mut log_viewer_query: Query<&mut Node, With<LogViewerMarker>>,
+    mut window_query: Query<&mut Window, With<LogViewerMarker>>,
     mut log_viewer_res: ResMut<LogViewerState>,
 ) {
     let visible = match trigger.event() {
@@ -259,6 +1123,9 @@ fn handle_log_viewer_visibilty(
         LogViewerVisibility::Toggle => !log_viewer_res.visible,
     };
 
+    for mut window in window_query.iter_mut() {
+        window.visible = visible;
+    }
     if visible {
         for mut style in log_viewer_query.iter_mut() {
             style.display = Display::Flex;
@@ -272,49 +1139,74 @@ fn handle_log_viewer_visibilty(
     }
 }
 
-fn handle_log_viewer_clear(
-    _trigger: On<ClearLogs>,
-    logs: Query<Entity, With<LogLineMarker>>,
+/// Hides the viewer instead of closing its own window, so it can be shown again.
+///
+/// Runs after `close_when_requested` to undo its [`ClosingWindow`] marker. With
+/// `WindowPlugin::close_when_requested` disabled nothing marks the window, so the ordering does
+/// nothing and the viewer is hidden all the same.
+fn hide_on_window_close(
+    mut close_requests: MessageReader<WindowCloseRequested>,
+    window_query: Query<(), (With<Window>, With<LogViewerMarker>)>,
+    mut commands: Commands,
+) {
+    for request in close_requests.read() {
+        if window_query.contains(request.window) {
+            // `close_when_requested` marked it to be despawned next frame.
+            commands.entity(request.window).remove::<ClosingWindow>();
+            commands.trigger(LogViewerVisibility::Hide);
+        }
+    }
+}
+
+/// Closes the viewer's own window once the last game window closed, so it doesn't keep the app
+/// running under `ExitCondition::OnAllClosed`.
+fn close_with_game_windows(
+    mut closed: MessageReader<WindowClosed>,
+    game_window_query: Query<(), (With<Window>, Without<LogViewerMarker>)>,
+    viewer_window_query: Query<Entity, (With<Window>, With<LogViewerMarker>)>,
     mut commands: Commands,
 ) {
-    for e in logs.iter() {
-        commands.entity(e).despawn();
+    if closed.read().count() > 0 && game_window_query.is_empty() {
+        for window in viewer_window_query.iter() {
+            commands.entity(window).despawn();
+        }
     }
 }
 
+fn handle_log_viewer_clear(
+    _trigger: On<ClearLogs>,
+    mut log_store: ResMut<LogStore>,
+    mut filtered: ResMut<FilteredLogs>,
+    mut targets: ResMut<LogTargets>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+) {
+    log_store.clear();
+    filtered.clear();
+    targets.clear();
+    log_viewer_res.detail_id = None;
+    #[cfg(feature = "clipboard")]
+    log_viewer_res.selected.clear();
+}
+
 fn handle_log_viewer_fullscreen(
     trigger: On<LogViewerSize>,
-    mut log_viewer_query: Query<&mut Node, With<LogViewerMarker>>,
     mut log_viewer_res: ResMut<LogViewerState>,
 ) {
-    match trigger.event() {
-        LogViewerSize::Big => {
-            for mut style in log_viewer_query.iter_mut() {
-                style.height = Val::Percent(100.0);
-            }
-            log_viewer_res.fullscreen = true;
-        }
-        LogViewerSize::Small => {
-            for mut style in log_viewer_query.iter_mut() {
-                style.height = Val::Percent(40.0);
-            }
-            log_viewer_res.fullscreen = false;
-        }
-        LogViewerSize::Toggle => {
-            for mut style in log_viewer_query.iter_mut() {
-                match style.height {
-                    Val::Percent(100.0) => {
-                        style.height = Val::Percent(40.0);
-                        log_viewer_res.fullscreen = false;
-                    }
-                    _ => {
-                        style.height = Val::Percent(100.0);
-                        log_viewer_res.fullscreen = true;
-                    }
-                }
-            }
-        }
+    // Leaving fullscreen with `Toggle` returns to floating if the viewer floated before.
+    let (fullscreen, floating) = match trigger.event() {
+        LogViewerSize::Big => (true, log_viewer_res.floating),
+        LogViewerSize::Small => (false, log_viewer_res.dock == LogViewerDock::Floating),
+        LogViewerSize::Toggle => (!log_viewer_res.fullscreen, log_viewer_res.floating),
+        LogViewerSize::Floating => (false, true),
+    };
+    // In its own window the viewer always fills it.
+    let floating = floating && !log_viewer_res.own_window;
+    // Going back to small undoes resizing with the handle.
+    if !fullscreen {
+        log_viewer_res.size = log_viewer_res.small_size;
     }
+    log_viewer_res.fullscreen = fullscreen;
+    log_viewer_res.floating = floating;
 }
 
 fn handle_auto_open_check(
@@ -333,203 +1225,256 @@ fn handle_auto_open_check(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn handle_level_filter_chip_toggle(
-    trigger: On<ChipToggle>,
-    mut chip_query: Query<
-        (&mut BackgroundColor, &mut BorderColor, &LevelFilterChip),
-        With<LevelFilterChip>,
-    >,
+#[allow(clippy::type_complexity)]
+fn handle_log_viewer_search(
+    trigger: On<LogViewerSearch>,
     mut log_viewer_res: ResMut<LogViewerState>,
-    mut err_logline_query: Query<&mut Node, WithOnlyErrLogLine>,
-    mut warn_logline_query: Query<&mut Node, WithOnlyWarnLogLine>,
-    mut info_logline_query: Query<&mut Node, WithOnlyInfoLogLine>,
-    mut debug_logline_query: Query<&mut Node, WithOnlyDebugLogLine>,
-    mut trace_logline_query: Query<&mut Node, WithOnlyTraceLogLine>,
+    log_store: Res<LogStore>,
+    mut filtered: ResMut<FilteredLogs>,
+    mut input_query: Query<&mut TextInput, With<SearchInputMarker>>,
+    mut chip_query: Query<&mut ToggleChip, With<RegexChip>>,
+    mut error_query: Query<(&mut Text, &mut Node), With<SearchErrorMarker>>,
 ) {
-    for (mut bg_color, mut border_color, chip) in chip_query.iter_mut() {
-        if trigger.event().0 == *chip {
-            let BackgroundColor(color) = *bg_color;
-
-            // If the color has an alpha value > 0, it means it's already selected.
-            if color.alpha() != 0. {
-                // Deselect the chip, make the background transparent and the border white.
-                *bg_color = color.with_alpha(0.).into();
-                *border_color = css::WHITE.into();
-                // Hide the log lines of the deselected chip.
-                match *chip {
-                    LevelFilterChip::Error => {
-                        for mut style in err_logline_query.iter_mut() {
-                            log_viewer_res.error_visible = false;
-                            style.display = Display::None;
-                        }
-                    }
-                    LevelFilterChip::Warn => {
-                        for mut style in warn_logline_query.iter_mut() {
-                            log_viewer_res.warn_visible = false;
-                            style.display = Display::None;
-                        }
-                    }
-                    LevelFilterChip::Info => {
-                        for mut style in info_logline_query.iter_mut() {
-                            log_viewer_res.info_visible = false;
-                            style.display = Display::None;
-                        }
-                    }
-                    LevelFilterChip::Debug => {
-                        for mut style in debug_logline_query.iter_mut() {
-                            log_viewer_res.debug_visible = false;
-                            style.display = Display::None;
-                        }
-                    }
-                    LevelFilterChip::Trace => {
-                        for mut style in trace_logline_query.iter_mut() {
-                            log_viewer_res.trace_visible = false;
-                            style.display = Display::None;
-                        }
-                    }
-                }
-            } else {
-                // Select the chip, make the background translucent and the border solid.
-                *bg_color = color.with_alpha(0.25).into();
-                *border_color = color.with_alpha(1.).into();
-                // Show the log lines that match the chip's level.
-                match *chip {
-                    LevelFilterChip::Error => {
-                        for mut style in err_logline_query.iter_mut() {
-                            log_viewer_res.error_visible = true;
-                            style.display = Display::Flex;
-                        }
-                    }
-                    LevelFilterChip::Warn => {
-                        for mut style in warn_logline_query.iter_mut() {
-                            log_viewer_res.warn_visible = true;
-                            style.display = Display::Flex;
-                        }
-                    }
-                    LevelFilterChip::Info => {
-                        for mut style in info_logline_query.iter_mut() {
-                            log_viewer_res.info_visible = true;
-                            style.display = Display::Flex;
-                        }
-                    }
-                    LevelFilterChip::Debug => {
-                        for mut style in debug_logline_query.iter_mut() {
-                            log_viewer_res.debug_visible = true;
-                            style.display = Display::Flex;
-                        }
-                    }
-                    LevelFilterChip::Trace => {
-                        for mut style in trace_logline_query.iter_mut() {
-                            log_viewer_res.trace_visible = true;
-                            style.display = Display::Flex;
-                        }
-                    }
-                }
+    let LogViewerSearch { query, regex } = trigger.event();
+
+    // An invalid pattern filters nothing; the reason is shown next to the search box.
+    let (search, error) = match SearchFilter::new(query, *regex) {
+        Ok(search) => (search, None),
+        Err(error) => (SearchFilter::None, Some(error)),
+    };
+    log_viewer_res.search = search;
+    log_viewer_res.search_query.clone_from(query);
+    log_viewer_res.search_regex = *regex;
+
+    for mut input in input_query.iter_mut() {
+        if input.value != *query {
+            input.value.clone_from(query);
+        }
+        if input.invalid != error.is_some() {
+            input.invalid = error.is_some();
+        }
+    }
+    for mut chip in chip_query.iter_mut() {
+        chip.active = *regex;
+    }
+    for (mut text, mut node) in error_query.iter_mut() {
+        match &error {
+            // The last line of a regex parse error names the problem, the lines above point at it.
+            Some(error) => {
+                text.0 = error
+                    .to_string()
+                    .lines()
+                    .last()
+                    .unwrap_or_default()
+                    .to_string();
+                node.display = Display::Flex;
             }
+            None => node.display = Display::None,
         }
     }
+
+    filtered.rebuild(&log_store, &log_viewer_res);
+}
+
+fn handle_log_viewer_target_filter(
+    trigger: On<LogViewerTargetFilter>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+    log_store: Res<LogStore>,
+    mut filtered: ResMut<FilteredLogs>,
+    mut input_query: Query<&mut TextInput, With<TargetDirectivesInputMarker>>,
+    mut error_query: Query<(&mut Text, &mut Node), With<TargetDirectivesErrorMarker>>,
+) {
+    let LogViewerTargetFilter(text) = trigger.event();
+
+    // Invalid directives filter nothing; the reason is shown next to the input.
+    let (directives, error) = match text.parse::<TargetDirectives>() {
+        Ok(directives) => (directives, None),
+        Err(error) => (TargetDirectives::default(), Some(error)),
+    };
+    log_viewer_res.target_directives = directives;
+    log_viewer_res.target_directives_text.clone_from(text);
+
+    for mut input in input_query.iter_mut() {
+        if input.value != *text {
+            input.value.clone_from(text);
+        }
+        if input.invalid != error.is_some() {
+            input.invalid = error.is_some();
+        }
+    }
+    for (mut error_text, mut node) in error_query.iter_mut() {
+        match &error {
+            Some(error) => {
+                error_text.0.clone_from(error);
+                node.display = Display::Flex;
+            }
+            None => node.display = Display::None,
+        }
+    }
+
+    filtered.rebuild(&log_store, &log_viewer_res);
+}
+
+fn handle_level_filter_chip_toggle(
+    trigger: On<ChipToggle>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+    log_store: Res<LogStore>,
+    mut filtered: ResMut<FilteredLogs>,
+) {
+    let toggled = trigger.event().0;
+    let visible = !log_viewer_res.level_visible(toggled.level());
+    match toggled {
+        LevelFilterChip::Error => log_viewer_res.error_visible = visible,
+        LevelFilterChip::Warn => log_viewer_res.warn_visible = visible,
+        LevelFilterChip::Info => log_viewer_res.info_visible = visible,
+        LevelFilterChip::Debug => log_viewer_res.debug_visible = visible,
+        LevelFilterChip::Trace => log_viewer_res.trace_visible = visible,
+    }
+
+    // Show or hide the log lines of the toggled level.
+    filtered.rebuild(&log_store, &log_viewer_res);
 }
 
 #[allow(clippy::type_complexity)]
 fn update_log_counts(
     chip_query: Query<(Entity, &LevelFilterChip), (With<ChipLeadingTextMarker>, With<Text>)>,
-    err_logline_query: Query<&mut Node, WithOnlyErrLogLine>,
-    warn_logline_query: Query<&mut Node, WithOnlyWarnLogLine>,
-    info_logline_query: Query<&mut Node, WithOnlyInfoLogLine>,
-    debug_logline_query: Query<&mut Node, WithOnlyDebugLogLine>,
-    trace_logline_query: Query<&mut Node, WithOnlyTraceLogLine>,
+    filtered: Res<FilteredLogs>,
     mut text_writer: TextUiWriter,
 ) {
+    if !filtered.is_changed() {
+        return;
+    }
+
     // Update the count of log lines for each chip.
     for (e, chip) in chip_query.iter() {
-        let count = match *chip {
-            LevelFilterChip::Error => err_logline_query.iter().count(),
-            LevelFilterChip::Warn => warn_logline_query.iter().count(),
-            LevelFilterChip::Info => info_logline_query.iter().count(),
-            LevelFilterChip::Debug => debug_logline_query.iter().count(),
-            LevelFilterChip::Trace => trace_logline_query.iter().count(),
-        };
-        *text_writer.text(e, 0) = count.to_string();
+        *text_writer.text(e, 0) = filtered.level_count(chip.level()).to_string();
+    }
+}
+
+/// Adds a record to the store and keeps the views over it up to date.
+fn store_log(
+    record: LogEvent,
+    log_store: &mut LogStore,
+    filtered: &mut FilteredLogs,
+    targets: &mut LogTargets,
+    log_viewer_res: &LogViewerState,
+) {
+    let id = log_store.next_id();
+    filtered.push(id, &record, log_store, log_viewer_res);
+    targets.add(record.target.clone());
+    if let Some(evicted) = log_store.push(record) {
+        filtered.evict(&evicted, log_store, log_viewer_res);
+        targets.remove(&evicted.target);
+    }
+}
+
+/// Shows the end of the previous session's log file before the logs of this session.
+fn load_previous_session(
+    sink: Option<Res<LogFileSink>>,
+    log_viewer_res: Res<LogViewerState>,
+    mut log_store: ResMut<LogStore>,
+    mut filtered: ResMut<FilteredLogs>,
+    mut targets: ResMut<LogTargets>,
+) {
+    let Some(sink) = sink else {
+        return;
+    };
+    match read_previous_session(&sink) {
+        Ok(records) => {
+            // Only what fits, so the old session's lines aren't counted as dropped.
+            let room = log_store.capacity().saturating_sub(log_store.len());
+            let skipped = records.len().saturating_sub(room);
+            for record in records.into_iter().skip(skipped) {
+                store_log(
+                    record,
+                    &mut log_store,
+                    &mut filtered,
+                    &mut targets,
+                    &log_viewer_res,
+                );
+            }
+        }
+        // There is no previous session on first launch.
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+        Err(error) => warn!(
+            "failed to load the previous session's logs from {}: {error}",
+            sink.path(1).display()
+        ),
     }
 }
 
 fn receive_logs(
     mut commands: Commands,
-    mut query: Query<Entity, With<ListMarker>>,
     log_viewer_res: Res<LogViewerState>,
-    logs_rx: Option<NonSend<LogEventsReceiver>>,
+    mut log_store: ResMut<LogStore>,
+    mut filtered: ResMut<FilteredLogs>,
+    mut targets: ResMut<LogTargets>,
+    logs_rx: Option<Res<LogEventsReceiver>>,
 ) {
     if let Some(receiver) = logs_rx {
-        for e in receiver.try_iter() {
-            if let Ok(parent) = query.single_mut() {
-                let child = spawn_logline(&mut commands, parent, &e);
-
-                // Insert the relevant log line marker and set visibility based on the log level.
-                match *e.metadata.level() {
-                    tracing::Level::ERROR => add_level_info(
-                        &mut commands,
-                        child,
-                        ErrLogLineMarker,
-                        log_viewer_res.error_visible,
-                    ),
-                    tracing::Level::WARN => add_level_info(
-                        &mut commands,
-                        child,
-                        WarnLogLineMarker,
-                        log_viewer_res.warn_visible,
-                    ),
-                    tracing::Level::INFO => add_level_info(
-                        &mut commands,
-                        child,
-                        InfoLogLineMarker,
-                        log_viewer_res.info_visible,
-                    ),
-                    tracing::Level::DEBUG => add_level_info(
-                        &mut commands,
-                        child,
-                        DebugLogLineMarker,
-                        log_viewer_res.debug_visible,
-                    ),
-                    tracing::Level::TRACE => add_level_info(
-                        &mut commands,
-                        child,
-                        TraceLogLineMarker,
-                        log_viewer_res.trace_visible,
-                    ),
-                };
+        let (records, lost) = receiver.drain();
+        if lost > 0 {
+            log_store.add_lost(lost);
+        }
+        for e in records {
+            if e.message.is_none() && !log_viewer_res.capture_without_message {
+                continue;
             }
+
             // If the log viewer is not visible, check if the log event should trigger it to open.
             if log_viewer_res.auto_open_enabled
                 && !log_viewer_res.visible
-                && *e.metadata.level() <= log_viewer_res.auto_open_threshold
+                && e.level <= log_viewer_res.auto_open_threshold
             {
                 commands.trigger(LogViewerVisibility::Show);
             }
             if log_viewer_res.scroll_state == ScrollState::Auto {
                 commands.trigger(ScrollToBottom);
             }
+
+            store_log(
+                e,
+                &mut log_store,
+                &mut filtered,
+                &mut targets,
+                &log_viewer_res,
+            );
         }
     }
 }
 
-fn add_level_info(
-    commands: &mut Commands,
-    child_entity: Entity,
-    level_marker: impl Component,
-    visible: bool,
+#[allow(clippy::type_complexity)]
+fn update_dropped_lines(
+    log_store: Res<LogStore>,
+    mut dropped_query: Query<(&mut Text, &mut Node), With<DroppedLinesMarker>>,
+    mut lost_query: Query<
+        (&mut Text, &mut Node),
+        (With<LostLogsMarker>, Without<DroppedLinesMarker>),
+    >,
 ) {
-    commands.entity(child_entity).insert((
-        level_marker,
-        Node {
-            display: if visible {
-                Display::Flex
-            } else {
-                Display::None
-            },
-            ..default()
-        },
-    ));
+    if !log_store.is_changed() {
+        return;
+    }
+
+    for (mut text, mut node) in lost_query.iter_mut() {
+        let lost = log_store.lost();
+        if lost == 0 {
+            node.display = Display::None;
+        } else {
+            text.0 = format!("{lost} logs lost");
+            node.display = Display::Flex;
+        }
+    }
+
+    for (mut text, mut node) in dropped_query.iter_mut() {
+        let dropped = log_store.dropped();
+        if dropped == 0 {
+            node.display = Display::None;
+        } else {
+            text.0 = format!("{dropped} lines dropped");
+            node.display = Display::Flex;
+        }
+    }
 }
 
 // Align the list to the End until it overflows, then switch to Default for scrolling to work.
@@ -553,6 +1498,7 @@ fn handle_listcontainer_overflow(
 
 fn manage_scroll_ui_state(
     log_viewer: Res<LogViewerState>,
+    theme: Res<LogViewerTheme>,
     mut border_color_q: Query<&mut BorderColor, With<LogViewerMarker>>,
     mut scroll_to_bottom_btn_q: Query<&mut Node, With<GoDownBtnMarker>>,
     mut scroll_query: Query<(&ScrollPosition, &ComputedNode, &Children), With<ListContainerMarker>>,
@@ -566,7 +1512,7 @@ fn manage_scroll_ui_state(
                 *border_color = if hide_button {
                     Color::NONE.into()
                 } else {
-                    css::WHITE.with_alpha(0.25).into()
+                    theme.border.into()
                 };
             }
             if let Ok(mut scroll_to_bottom_btn) = scroll_to_bottom_btn_q.single_mut() {
@@ -583,70 +1529,15 @@ fn manage_scroll_ui_state(
 fn handle_scroll_to_bottom(
     _trigger: On<ScrollToBottom>,
     mut log_viewer: ResMut<LogViewerState>,
-    mut scroll_query: Query<
-        (&mut ScrollPosition, &ComputedNode, &Children),
-        With<ListContainerMarker>,
-    >,
-    computed_node_query: Query<&ComputedNode, With<ListMarker>>,
+    filtered: Res<FilteredLogs>,
+    font_size: Res<LogFontSize>,
+    mut scroll_query: Query<(&mut ScrollPosition, &ComputedNode), With<ListContainerMarker>>,
 ) {
-    // ListContainerMarker -> ListMarker have a Parent -> Child relationship.
-    if let Ok((mut scroll_position, container, children)) = scroll_query.single_mut() {
-        if let Ok(computed_node) = computed_node_query.get(children[0]) {
-            scroll_position.y = crate::utils::max_scroll(container, computed_node).y;
-            log_viewer.scroll_state = ScrollState::Auto;
-        }
-    }
-}
-
-fn spawn_logline(commands: &mut Commands, parent: Entity, event: &LogEvent) -> Entity {
-    let dbg_level = DebugLogLevel::from(*event.metadata.level());
-
-    commands
-        .spawn((
-            Pickable {
-                should_block_lower: false,
-                ..default()
-            },
-            TextLayout::default().with_linebreak(LineBreak::AnyCharacter),
-            Text::default(),
-            // Label,
-            LogLineMarker,
-        ))
-        .with_child((
-            TextSpan::new(
-                event
-                    .timestamp
-                    .format(&iso8601::Iso8601::<
-                        {
-                            iso8601::Config::DEFAULT
-                                .set_time_precision(iso8601::TimePrecision::Second {
-                                    decimal_digits: Some(NonZero::new(2).unwrap()),
-                                })
-                                .encode()
-                        },
-                    > {})
-                    .unwrap_or("timestamp error".to_string()),
-            ),
-            TextFont::from_font_size(LOG_LINE_FONT_SIZE),
-            TextColor(css::WHITE.with_alpha(0.5).into()),
-        ))
-        .with_child((
-            TextSpan::new(format!(" {} ", dbg_level)),
-            TextFont::from_font_size(LOG_LINE_FONT_SIZE),
-            TextColor(dbg_level.into()),
-        ))
-        .with_child((
-            TextSpan::new(format!("{}: ", event.metadata.target())),
-            TextFont::from_font_size(LOG_LINE_FONT_SIZE),
-            TextColor(css::WHITE.with_alpha(0.5).into()),
-        ))
-        .with_child((
-            TextSpan::new(event.message.clone()),
-            TextFont::from_font_size(LOG_LINE_FONT_SIZE),
-            TextColor(css::WHITE.into()),
-        ))
-        .insert(ChildOf(parent))
-        .id()
+    if let Ok((mut scroll_position, container)) = scroll_query.single_mut() {
+        scroll_position.y =
+            log_list::max_scroll_y(container, filtered.len(), font_size.row_height());
+        log_viewer.scroll_state = ScrollState::Auto;
+    }
 }
 
 fn on_traffic_light_button(
@@ -686,24 +1577,43 @@ fn on_level_filter_chip(
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn handle_scroll_update(
     mut mouse_wheel_events: MessageReader<MouseWheel>,
     hover_map: Res<HoverMap>,
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut font_size: ResMut<LogFontSize>,
+    container_query: Query<(), With<ListContainerMarker>>,
     mut scrolled_node_query: Query<
         (&mut ScrollPosition, &ComputedNode, &Children),
-        With<ListContainerMarker>,
+        Or<(With<ListContainerMarker>, With<DetailScrollMarker>)>,
     >,
-    content_query: Query<&ComputedNode, With<ListMarker>>,
+    content_query: Query<&ComputedNode, Or<(With<ListMarker>, With<DetailTextMarker>)>>,
 ) {
     for mouse_wheel_event in mouse_wheel_events.read() {
         let (dx, dy) = match mouse_wheel_event.unit {
             MouseScrollUnit::Line => (
-                mouse_wheel_event.x * LOG_LINE_FONT_SIZE,
-                mouse_wheel_event.y * LOG_LINE_FONT_SIZE,
+                mouse_wheel_event.x * font_size.0,
+                mouse_wheel_event.y * font_size.0,
             ),
             MouseScrollUnit::Pixel => (mouse_wheel_event.x, mouse_wheel_event.y),
         };
 
+        // Ctrl+scroll over the list zooms it instead.
+        let ctrl = keys.as_ref().is_some_and(|keys| {
+            keys.any_pressed([
+                KeyCode::ControlLeft,
+                KeyCode::ControlRight,
+                KeyCode::SuperLeft,
+                KeyCode::SuperRight,
+            ])
+        });
+        if ctrl && zoom::hovers_list(&hover_map, &PointerId::Mouse, &container_query) {
+            let lines = dy / font_size.0;
+            zoom::zoom(&mut font_size, zoom::SCROLL_ZOOM_STEP.powf(lines));
+            continue;
+        }
+
         for (_pointer, pointer_map) in hover_map.iter() {
             for (entity, _hit) in pointer_map.iter() {
                 if let Ok((mut scroll_position, container, children)) =
@@ -719,3 +1629,238 @@ fn handle_scroll_update(
         }
     }
 }
+
+fn on_text_input_focus(
+    mut inputs: Query<(Entity, Ref<Interaction>, &mut TextInput)>,
+    mouse: Option<Res<ButtonInput<MouseButton>>>,
+    touches: Option<Res<Touches>>,
+) {
+    let pressed = inputs
+        .iter()
+        .find(|(_, interaction, _)| {
+            interaction.is_changed() && matches!(**interaction, Interaction::Pressed)
+        })
+        .map(|(entity, _, _)| entity);
+
+    // Pressing anywhere but on an input unfocuses all of them. Apps without a mouse or touch
+    // screen lack the resource.
+    if pressed.is_none()
+        && !mouse.is_some_and(|mouse| mouse.just_pressed(MouseButton::Left))
+        && touches.is_none_or(|touches| touches.iter_just_pressed().next().is_none())
+    {
+        return;
+    }
+
+    for (entity, _, mut input) in inputs.iter_mut() {
+        let focused = pressed == Some(entity);
+        if input.focused != focused {
+            input.focused = focused;
+        }
+    }
+}
+
+fn on_text_input_keyboard(
+    mut keyboard_events: MessageReader<KeyboardInput>,
+    mut inputs: Query<&mut TextInput>,
+) {
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        for mut input in inputs.iter_mut().filter(|input| input.focused) {
+            match &event.logical_key {
+                Key::Backspace => {
+                    input.value.pop();
+                }
+                Key::Enter | Key::Escape => input.focused = false,
+                _ => {
+                    if let Some(text) = &event.text {
+                        input.value.extend(text.chars().filter(|c| !c.is_control()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn update_text_input(
+    theme: Res<LogViewerTheme>,
+    mut inputs: Query<(Ref<TextInput>, &Children, &mut BorderColor)>,
+    mut texts: Query<(&mut Text, &mut ThemedText), With<TextInputTextMarker>>,
+) {
+    for (input, children, mut border_color) in inputs.iter_mut() {
+        if !input.is_changed() && !theme.is_changed() {
+            continue;
+        }
+        let color = match (input.invalid, input.focused) {
+            (true, true) => theme.error,
+            (true, false) => theme.error.with_alpha(0.5),
+            (false, true) => theme.text,
+            (false, false) => theme.border,
+        };
+        *border_color = BorderColor::all(color);
+
+        let Some((mut text, mut color)) = children
+            .first()
+            .and_then(|child| texts.get_mut(*child).ok())
+        else {
+            continue;
+        };
+        if input.value.is_empty() && !input.focused {
+            text.0.clone_from(&input.placeholder);
+            color.set_if_neq(ThemedText(ThemeColor::MutedText));
+        } else {
+            // Show a caret at the end of the value while typing.
+            text.0 = format!("{}{}", input.value, if input.focused { "|" } else { "" });
+            color.set_if_neq(ThemedText(ThemeColor::Text));
+        }
+    }
+}
+
+fn on_search_input(
+    search_query: Query<&TextInput, (Changed<TextInput>, With<SearchInputMarker>)>,
+    log_viewer_res: Res<LogViewerState>,
+    mut commands: Commands,
+) {
+    for input in search_query.iter() {
+        if input.value != log_viewer_res.search_query {
+            commands.trigger(LogViewerSearch {
+                query: input.value.clone(),
+                regex: log_viewer_res.search_regex,
+            });
+        }
+    }
+}
+
+fn on_regex_chip(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<RegexChip>)>,
+    log_viewer_res: Res<LogViewerState>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if matches!(*interaction, Interaction::Pressed) {
+            commands.trigger(LogViewerSearch {
+                query: log_viewer_res.search_query.clone(),
+                regex: !log_viewer_res.search_regex,
+            });
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn on_targets_chip(
+    mut chip_query: Query<
+        (&Interaction, &mut ToggleChip),
+        (Changed<Interaction>, With<TargetsChip>),
+    >,
+    mut panel_query: Query<&mut Node, With<TargetPanelMarker>>,
+) {
+    for (interaction, mut chip) in chip_query.iter_mut() {
+        if matches!(*interaction, Interaction::Pressed) {
+            for mut node in panel_query.iter_mut() {
+                let open = node.display == Display::None;
+                node.display = if open { Display::Flex } else { Display::None };
+                chip.active = open;
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn on_target_toggle(
+    mut toggle_query: Query<(&Interaction, &TargetToggle, &mut ToggleChip), Changed<Interaction>>,
+    mut log_viewer_res: ResMut<LogViewerState>,
+    log_store: Res<LogStore>,
+    mut filtered: ResMut<FilteredLogs>,
+) {
+    for (interaction, TargetToggle(target), mut chip) in toggle_query.iter_mut() {
+        if matches!(*interaction, Interaction::Pressed) {
+            let visible = log_viewer_res.hidden_targets.remove(target);
+            if !visible {
+                log_viewer_res.hidden_targets.insert(target.clone());
+            }
+            chip.active = visible;
+            filtered.rebuild(&log_store, &log_viewer_res);
+        }
+    }
+}
+
+fn on_target_directives_input(
+    input_query: Query<&TextInput, (Changed<TextInput>, With<TargetDirectivesInputMarker>)>,
+    log_viewer_res: Res<LogViewerState>,
+    mut commands: Commands,
+) {
+    for input in input_query.iter() {
+        if input.value != log_viewer_res.target_directives_text {
+            commands.trigger(LogViewerTargetFilter(input.value.clone()));
+        }
+    }
+}
+
+/// Adds a toggle for every newly seen target and keeps the line counts up to date.
+fn sync_target_panel(
+    mut commands: Commands,
+    targets: Res<LogTargets>,
+    log_viewer_res: Res<LogViewerState>,
+    list_query: Query<Entity, With<TargetListMarker>>,
+    toggle_query: Query<(&TargetToggle, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !targets.is_changed() {
+        return;
+    }
+    let Ok(list) = list_query.single() else {
+        return;
+    };
+
+    let toggles: HashMap<&str, &Children> = toggle_query
+        .iter()
+        .map(|(TargetToggle(target), children)| (target.as_ref(), children))
+        .collect();
+
+    for (target, count) in targets.iter() {
+        let label = format!("{target} {count}");
+        match toggles.get(target.as_ref()) {
+            Some(children) => {
+                if let Some(mut text) = children
+                    .first()
+                    .and_then(|child| text_query.get_mut(*child).ok())
+                {
+                    if text.0 != label {
+                        text.0 = label;
+                    }
+                }
+            }
+            None => {
+                commands.spawn((
+                    utils::toggle_chip(
+                        TargetToggle(target.clone()),
+                        label,
+                        !log_viewer_res.hidden_targets.contains(target),
+                        "target_toggle",
+                    ),
+                    ChildOf(list),
+                ));
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn on_collapse_chip(
+    mut chip_query: Query<
+        (&Interaction, &mut ToggleChip),
+        (Changed<Interaction>, With<CollapseChip>),
+    >,
+    mut log_viewer_res: ResMut<LogViewerState>,
+    log_store: Res<LogStore>,
+    mut filtered: ResMut<FilteredLogs>,
+) {
+    for (interaction, mut chip) in chip_query.iter_mut() {
+        if matches!(*interaction, Interaction::Pressed) {
+            log_viewer_res.collapse_repeats = !log_viewer_res.collapse_repeats;
+            chip.active = log_viewer_res.collapse_repeats;
+            filtered.rebuild(&log_store, &log_viewer_res);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Per-callsite rate limiting of the capture, protecting the app from logging in hot loops.
+
+use bevy_log::tracing::{callsite::Identifier, Metadata};
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{Mutex, PoisonError},
+};
+use time::{Duration, OffsetDateTime};
+
+/// Logs of one callsite suppressed since its last summary.
+pub(crate) type Suppressed = (&'static Metadata<'static>, usize);
+
+/// A token bucket per callsite, refilled with `per_second` tokens a second and holding at most
+/// as many, so a callsite can burst up to a second's worth of logs.
+pub(crate) struct RateLimiter {
+    per_second: f64,
+    state: Mutex<RateLimiterState>,
+}
+
+struct RateLimiterState {
+    buckets: HashMap<Identifier, Bucket>,
+    /// When callsites that went quiet were last checked for unreported suppressed logs.
+    last_flush: OffsetDateTime,
+}
+
+struct Bucket {
+    metadata: &'static Metadata<'static>,
+    tokens: f64,
+    refilled: OffsetDateTime,
+    suppressed: usize,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(per_second: u32) -> Self {
+        Self {
+            per_second: per_second.max(1) as f64,
+            state: Mutex::new(RateLimiterState {
+                buckets: HashMap::new(),
+                last_flush: OffsetDateTime::UNIX_EPOCH,
+            }),
+        }
+    }
+
+    /// Whether a log of the callsite may be captured at `now`, along with the suppressed logs
+    /// that are due to be reported.
+    pub(crate) fn check(
+        &self,
+        metadata: &'static Metadata<'static>,
+        now: OffsetDateTime,
+    ) -> (bool, Vec<Suppressed>) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut due = Vec::new();
+
+        let bucket = state
+            .buckets
+            .entry(metadata.callsite())
+            .or_insert_with(|| Bucket {
+                metadata,
+                tokens: self.per_second,
+                refilled: now,
+                suppressed: 0,
+            });
+        let elapsed = (now - bucket.refilled).as_seconds_f64().max(0.);
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.per_second);
+        bucket.refilled = now;
+
+        let allowed = bucket.tokens >= 1.;
+        if allowed {
+            bucket.tokens -= 1.;
+            if bucket.suppressed > 0 {
+                due.push((metadata, mem::take(&mut bucket.suppressed)));
+            }
+        } else {
+            bucket.suppressed += 1;
+        }
+
+        // Callsites that stopped logging while limited would never report their suppressed logs
+        // otherwise.
+        if now - state.last_flush >= Duration::SECOND {
+            state.last_flush = now;
+            for bucket in state.buckets.values_mut() {
+                if bucket.suppressed > 0 && now - bucket.refilled >= Duration::SECOND {
+                    due.push((bucket.metadata, mem::take(&mut bucket.suppressed)));
+                }
+            }
+        }
+
+        (allowed, due)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_log::tracing::{
+        callsite::{Callsite, Identifier},
+        field::FieldSet,
+        metadata::Kind,
+        subscriber::Interest,
+        Level,
+    };
+    use pretty_assertions::assert_eq;
+
+    /// A static callsite with metadata named `$name`, like the ones `info!` generates.
+    macro_rules! callsite {
+        ($callsite:ident, $metadata:ident, $name:literal) => {
+            struct $callsite;
+            static $metadata: Metadata<'static> = Metadata::new(
+                $name,
+                "test",
+                Level::INFO,
+                None,
+                None,
+                None,
+                FieldSet::new(&[], Identifier(&$callsite)),
+                Kind::EVENT,
+            );
+            impl Callsite for $callsite {
+                fn set_interest(&self, _: Interest) {}
+                fn metadata(&self) -> &Metadata<'_> {
+                    &$metadata
+                }
+            }
+        };
+    }
+
+    callsite!(FirstCallsite, FIRST, "first");
+    callsite!(SecondCallsite, SECOND, "second");
+
+    fn at(seconds: f64) -> OffsetDateTime {
+        OffsetDateTime::UNIX_EPOCH + Duration::seconds_f64(seconds)
+    }
+
+    fn suppressed(due: Vec<Suppressed>) -> Vec<(&'static str, usize)> {
+        due.into_iter()
+            .map(|(metadata, count)| (metadata.name(), count))
+            .collect()
+    }
+
+    #[test]
+    fn allows_a_burst_of_per_second() {
+        let limiter = RateLimiter::new(3);
+        let allowed: Vec<_> = (0..5).map(|_| limiter.check(&FIRST, at(0.)).0).collect();
+        assert_eq!(allowed, vec![true, true, true, false, false]);
+    }
+
+    #[test]
+    fn refills_over_time_and_reports_suppressed() {
+        let limiter = RateLimiter::new(2);
+        for _ in 0..5 {
+            limiter.check(&FIRST, at(0.));
+        }
+
+        assert!(!limiter.check(&FIRST, at(0.25)).0);
+        let (allowed, due) = limiter.check(&FIRST, at(0.5));
+        assert!(allowed);
+        assert_eq!(suppressed(due), vec![("first", 4)]);
+    }
+
+    #[test]
+    fn limits_callsites_separately() {
+        let limiter = RateLimiter::new(1);
+        assert!(limiter.check(&FIRST, at(0.)).0);
+        assert!(!limiter.check(&FIRST, at(0.)).0);
+        assert!(limiter.check(&SECOND, at(0.)).0);
+    }
+
+    #[test]
+    fn reports_callsites_that_went_quiet() {
+        let limiter = RateLimiter::new(1);
+        limiter.check(&FIRST, at(0.));
+        limiter.check(&FIRST, at(0.));
+
+        let (allowed, due) = limiter.check(&SECOND, at(2.));
+        assert!(allowed);
+        assert_eq!(suppressed(due), vec![("first", 1)]);
+        assert!(limiter.check(&SECOND, at(4.)).1.is_empty());
+    }
+
+    #[test]
+    fn zero_is_raised_to_one() {
+        let limiter = RateLimiter::new(0);
+        assert!(limiter.check(&FIRST, at(0.)).0);
+        assert!(!limiter.check(&FIRST, at(0.)).0);
+    }
+}
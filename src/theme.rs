@@ -0,0 +1,315 @@
+//! Colours, font and sizes of the viewer, applied to the UI whenever they change.
+
+use crate::{
+    log_viewer::{LevelFilterChip, LogViewerState},
+    utils::{ChipCircleMarker, ToggleChip},
+};
+use bevy_color::{palettes::css, prelude::*};
+use bevy_ecs::prelude::*;
+use bevy_log::Level;
+use bevy_text::{prelude::*, FontSource};
+use bevy_ui::prelude::*;
+use bevy_utils::prelude::*;
+
+/// Colours, font and sizes of the viewer, set via
+/// [`LogViewerPlugin::theme`](crate::LogViewerPlugin::theme). Changing the resource restyles the
+/// open viewer.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct LogViewerTheme {
+    /// Background of the viewer.
+    pub background: Color,
+    /// Background of panels covering the log list, like the log details.
+    pub panel_background: Color,
+    /// Regular text and icons.
+    pub text: Color,
+    /// Placeholders, timestamps, spans and targets.
+    pub muted_text: Color,
+    /// Text on the level colours of the level chips.
+    pub contrast_text: Color,
+    /// Borders of controls and of the scrolled list.
+    pub border: Color,
+    /// Background of switched on toggle chips.
+    pub active: Color,
+    /// Errors, like an invalid search or a failed export.
+    pub error: Color,
+    /// Warnings, like dropped or lost logs.
+    pub warning: Color,
+    /// Repeat count of collapsed lines.
+    pub repeat: Color,
+    /// Structured fields after the message.
+    pub fields: Color,
+    /// Background of lines selected for copying.
+    pub selection: Color,
+    /// Background of the previous session's lines.
+    pub previous_session: Color,
+    /// Trace level name in log lines and the trace chip.
+    pub level_trace: Color,
+    /// Debug level name in log lines and the debug chip.
+    pub level_debug: Color,
+    /// Info level name in log lines and the info chip.
+    pub level_info: Color,
+    /// Warn level name in log lines and the warn chip.
+    pub level_warn: Color,
+    /// Error level name in log lines and the error chip.
+    pub level_error: Color,
+    /// The button hiding the viewer.
+    pub close_button: Color,
+    /// The button clearing the logs.
+    pub clear_button: Color,
+    /// The button toggling fullscreen.
+    pub size_button: Color,
+    /// Font of all text, e.g. a `Handle<Font>`. Bevy's default font unless set.
+    pub font: FontSource,
+    /// Size of the title bar and panel text in logical px.
+    pub font_size: f32,
+}
+
+impl Default for LogViewerTheme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl LogViewerTheme {
+    /// Light text on a translucent dark background, the default.
+    pub fn dark() -> Self {
+        Self {
+            background: Color::srgba(0.15, 0.15, 0.15, 0.75),
+            panel_background: Color::srgba(0.1, 0.1, 0.1, 0.95),
+            text: Color::WHITE,
+            muted_text: Color::WHITE.with_alpha(0.5),
+            contrast_text: Color::BLACK,
+            border: Color::WHITE.with_alpha(0.5),
+            active: Color::WHITE.with_alpha(0.25),
+            error: css::RED.into(),
+            warning: css::ORANGE.into(),
+            repeat: css::GOLD.into(),
+            fields: css::SKY_BLUE.into(),
+            selection: Color::WHITE.with_alpha(0.15),
+            previous_session: css::ORANGE.with_alpha(0.1).into(),
+            level_trace: css::MEDIUM_ORCHID.into(),
+            level_debug: css::DEEP_SKY_BLUE.into(),
+            level_info: css::LIME.into(),
+            level_warn: css::YELLOW.into(),
+            level_error: css::RED.into(),
+            close_button: Color::srgb_u8(255, 95, 87),
+            clear_button: Color::srgb_u8(255, 188, 46),
+            size_button: Color::srgb_u8(43, 198, 63),
+            font: FontSource::default(),
+            font_size: 10.,
+        }
+    }
+
+    /// Dark text on a translucent light background.
+    pub fn light() -> Self {
+        Self {
+            background: Color::srgba(0.96, 0.96, 0.96, 0.85),
+            panel_background: Color::srgba(1., 1., 1., 0.97),
+            text: Color::srgb(0.1, 0.1, 0.1),
+            muted_text: Color::BLACK.with_alpha(0.55),
+            contrast_text: Color::WHITE,
+            border: Color::BLACK.with_alpha(0.4),
+            active: Color::BLACK.with_alpha(0.15),
+            error: css::CRIMSON.into(),
+            warning: css::DARK_ORANGE.into(),
+            repeat: css::DARK_GOLDENROD.into(),
+            fields: css::TEAL.into(),
+            selection: Color::BLACK.with_alpha(0.1),
+            previous_session: css::ORANGE.with_alpha(0.15).into(),
+            level_trace: css::DARK_VIOLET.into(),
+            level_debug: css::ROYAL_BLUE.into(),
+            level_info: css::GREEN.into(),
+            level_warn: css::DARK_ORANGE.into(),
+            level_error: css::CRIMSON.into(),
+            ..Self::dark()
+        }
+    }
+
+    /// Opaque black background, pure colours and larger text.
+    pub fn high_contrast() -> Self {
+        Self {
+            background: Color::BLACK,
+            panel_background: Color::BLACK,
+            text: Color::WHITE,
+            muted_text: Color::srgb(0.8, 0.8, 0.8),
+            contrast_text: Color::BLACK,
+            border: Color::WHITE,
+            active: Color::WHITE.with_alpha(0.4),
+            error: css::RED.into(),
+            warning: css::YELLOW.into(),
+            repeat: css::YELLOW.into(),
+            fields: css::AQUA.into(),
+            selection: css::NAVY.into(),
+            previous_session: Color::srgb(0.25, 0.15, 0.),
+            level_trace: css::VIOLET.into(),
+            level_debug: css::AQUA.into(),
+            level_info: css::LIME.into(),
+            level_warn: css::YELLOW.into(),
+            level_error: css::RED.into(),
+            font_size: 12.,
+            ..Self::dark()
+        }
+    }
+
+    /// Colour of the level's name in log lines and of its chip.
+    pub fn level_color(&self, level: Level) -> Color {
+        match level {
+            Level::TRACE => self.level_trace,
+            Level::DEBUG => self.level_debug,
+            Level::INFO => self.level_info,
+            Level::WARN => self.level_warn,
+            Level::ERROR => self.level_error,
+        }
+    }
+
+    pub(crate) fn color(&self, role: ThemeColor) -> Color {
+        match role {
+            ThemeColor::Background => self.background,
+            ThemeColor::PanelBackground => self.panel_background,
+            ThemeColor::Text => self.text,
+            ThemeColor::MutedText => self.muted_text,
+            ThemeColor::ContrastText => self.contrast_text,
+            ThemeColor::Error => self.error,
+            ThemeColor::Warning => self.warning,
+            ThemeColor::CloseButton => self.close_button,
+            ThemeColor::ClearButton => self.clear_button,
+            ThemeColor::SizeButton => self.size_button,
+        }
+    }
+
+    /// Font of the title bar and panel text.
+    pub(crate) fn text_font(&self) -> TextFont {
+        TextFont {
+            font: self.font.clone(),
+            font_size: FontSize::Px(self.font_size),
+            ..default()
+        }
+    }
+
+    /// Font of the log lines, in their own size.
+    pub(crate) fn log_font(&self, size: &LogFontSize) -> TextFont {
+        TextFont {
+            font: self.font.clone(),
+            font_size: FontSize::Px(size.0),
+            ..default()
+        }
+    }
+}
+
+/// Size of the log lines in logical px, set via
+/// [`LogViewerPlugin::log_font_size`](crate::LogViewerPlugin::log_font_size) and changed by
+/// zooming the list. Kept out of the [`LogViewerTheme`] so a zoom only restyles the log lines.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct LogFontSize(pub f32);
+
+impl Default for LogFontSize {
+    fn default() -> Self {
+        Self(8.)
+    }
+}
+
+impl LogFontSize {
+    /// Height in logical px of a log line.
+    pub(crate) fn row_height(&self) -> f32 {
+        self.0 * 1.25
+    }
+}
+
+/// A colour of the [`LogViewerTheme`] that UI elements refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ThemeColor {
+    Background,
+    PanelBackground,
+    Text,
+    MutedText,
+    ContrastText,
+    Error,
+    Warning,
+    CloseButton,
+    ClearButton,
+    SizeButton,
+}
+
+/// Text coloured by the theme and set in its font and text size.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ThemedText(pub(crate) ThemeColor);
+
+/// A node whose background is coloured by the theme.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ThemedBackground(pub(crate) ThemeColor);
+
+/// A node whose border is coloured by the theme.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ThemedBorder(pub(crate) ThemeColor);
+
+/// Restyles themed elements whose role changed, or all of them when the theme changed.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_theme(
+    theme: Res<LogViewerTheme>,
+    mut text_query: Query<(Ref<ThemedText>, &mut TextColor, &mut TextFont)>,
+    mut background_query: Query<(Ref<ThemedBackground>, &mut BackgroundColor)>,
+    mut border_query: Query<(Ref<ThemedBorder>, &mut BorderColor)>,
+    mut toggle_chip_query: Query<
+        (Ref<ToggleChip>, &mut BackgroundColor, &mut BorderColor),
+        Without<ThemedBackground>,
+    >,
+) {
+    let restyle = theme.is_changed();
+
+    for (role, mut color, mut font) in text_query.iter_mut() {
+        if restyle || role.is_changed() {
+            color.0 = theme.color(role.0);
+            *font = theme.text_font();
+        }
+    }
+    for (role, mut background) in background_query.iter_mut() {
+        if restyle || role.is_changed() {
+            background.0 = theme.color(role.0);
+        }
+    }
+    for (role, mut border) in border_query.iter_mut() {
+        if restyle || role.is_changed() {
+            *border = BorderColor::all(theme.color(role.0));
+        }
+    }
+    for (chip, mut background, mut border) in toggle_chip_query.iter_mut() {
+        if restyle || chip.is_changed() {
+            (*background, *border) = if chip.active {
+                (BackgroundColor(theme.active), BorderColor::all(theme.text))
+            } else {
+                (BackgroundColor(Color::NONE), BorderColor::all(theme.border))
+            };
+        }
+    }
+}
+
+/// Colours the level chips in their level's colour while their level is shown.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_level_chips(
+    theme: Res<LogViewerTheme>,
+    log_viewer_res: Res<LogViewerState>,
+    mut chip_query: Query<
+        (&LevelFilterChip, &mut BackgroundColor, &mut BorderColor),
+        Without<ChipCircleMarker>,
+    >,
+    mut circle_query: Query<(&LevelFilterChip, &mut BackgroundColor), With<ChipCircleMarker>>,
+) {
+    if !theme.is_changed() && !log_viewer_res.is_changed() {
+        return;
+    }
+
+    for (chip, mut background, mut border) in chip_query.iter_mut() {
+        let level = chip.level();
+        let color = theme.level_color(level);
+        let (new_background, new_border) = if log_viewer_res.level_visible(level) {
+            (color.with_alpha(0.25), color)
+        } else {
+            (color.with_alpha(0.), theme.text)
+        };
+        background.0 = new_background;
+        *border = BorderColor::all(new_border);
+    }
+    for (chip, mut background) in circle_query.iter_mut() {
+        background.0 = theme.level_color(chip.level());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::theme::{ThemeColor, ThemedBackground, ThemedBorder, ThemedText};
 use bevy_color::prelude::*;
 use bevy_ecs::{
     prelude::*,
@@ -16,11 +17,9 @@ pub(crate) fn max_scroll(container: &ComputedNode, content: &ComputedNode) -> Ve
     (content.size() - container.size()).max(Vec2::ZERO) * container.inverse_scale_factor
 }
 
-fn small_text() -> TextFont {
-    TextFont {
-        font_size: FontSize::Px(10.),
-        ..default()
-    }
+/// Colour, font and size of title bar and panel text, all set by the theme.
+pub(crate) fn text_style(color: ThemeColor) -> impl Bundle {
+    (TextFont::default(), ThemedText(color))
 }
 
 #[derive(Component)]
@@ -51,7 +50,7 @@ pub(crate) fn checkbox<B: Bundle + Clone>(
                     border_radius: BorderRadius::all(Val::Px(5.)),
                     ..default()
                 },
-                BorderColor::all(Color::WHITE),
+                ThemedBorder(ThemeColor::Text),
                 Name::new("check_box_button"),
                 bundle,
                 children![(
@@ -67,7 +66,7 @@ pub(crate) fn checkbox<B: Bundle + Clone>(
                         border_radius: BorderRadius::all(Val::Px(3.)),
                         ..default()
                     },
-                    BackgroundColor(Color::WHITE),
+                    ThemedBackground(ThemeColor::Text),
                     CheckboxIconMarker,
                     Name::new("check_box_icon"),
                 )],
@@ -80,7 +79,7 @@ pub(crate) fn checkbox<B: Bundle + Clone>(
                 ..default()
             },
             Name::new("check_box_label"),
-            children![(Text::new(text), small_text(), Label)],
+            children![(Text::new(text), text_style(ThemeColor::Text), Label)],
         )),
     )
 }
@@ -91,10 +90,16 @@ struct ChipMarker;
 #[derive(Component)]
 pub(crate) struct ChipLeadingTextMarker;
 
-/// A level filter chip: a message count in a coloured circle, followed by a label.
+/// The coloured circle of a level filter chip.
+#[derive(Component)]
+pub(crate) struct ChipCircleMarker;
+
+/// A level filter chip: a message count in a coloured circle, followed by a label. Its border is
+/// `color` while active and `inactive_border`, the theme's text colour, otherwise.
 pub(crate) fn chip<B: Bundle + Clone>(
     bundle: B,
     color: Color,
+    inactive_border: Color,
     leading_text: String,
     label_text: String,
     active: bool,
@@ -110,7 +115,7 @@ pub(crate) fn chip<B: Bundle + Clone>(
             border_radius: BorderRadius::all(Val::Px(20.)),
             ..default()
         },
-        BorderColor::all(if active { color } else { Color::WHITE }),
+        BorderColor::all(if active { color } else { inactive_border }),
         BackgroundColor(color.with_alpha(if active { 0.25 } else { 0. })),
         ChipMarker,
         bundle.clone(),
@@ -128,6 +133,8 @@ pub(crate) fn chip<B: Bundle + Clone>(
                     ..default()
                 },
                 BackgroundColor(color),
+                ChipCircleMarker,
+                bundle.clone(),
                 Name::new("chip_leading"),
                 children![(
                     Text::new(leading_text),
@@ -138,9 +145,7 @@ pub(crate) fn chip<B: Bundle + Clone>(
                         margin: UiRect::all(Val::Px(2.)),
                         ..default()
                     },
-                    small_text(),
-                    TextColor(Color::BLACK),
-                    BackgroundColor(color),
+                    text_style(ThemeColor::ContrastText),
                     Label,
                     bundle,
                     ChipLeadingTextMarker,
@@ -155,10 +160,89 @@ pub(crate) fn chip<B: Bundle + Clone>(
                     flex_grow: 1.,
                     ..default()
                 },
-                small_text(),
+                text_style(ThemeColor::Text),
                 Label,
                 Name::new("chip_label"),
             ),
         ],
     )
 }
+
+/// A single-line text input. Clicking it focuses it, while focused it takes keyboard input;
+/// clicking elsewhere, Enter or Escape unfocus it.
+#[derive(Component, Default)]
+pub(crate) struct TextInput {
+    pub(crate) value: String,
+    pub(crate) placeholder: String,
+    pub(crate) focused: bool,
+    /// Draws the border red, e.g. while the value fails to parse.
+    pub(crate) invalid: bool,
+}
+
+#[derive(Component)]
+pub(crate) struct TextInputTextMarker;
+
+/// A text input: a bordered box showing its value, or a dimmed placeholder while empty.
+pub(crate) fn text_input<B: Bundle>(bundle: B, name: &str, placeholder: &str) -> impl Bundle {
+    (
+        Button,
+        Node {
+            width: Val::Percent(100.),
+            min_height: Val::Px(20.),
+            align_self: AlignSelf::Center,
+            align_items: AlignItems::Center,
+            padding: UiRect::horizontal(Val::Px(5.)),
+            margin: UiRect::all(Val::Px(1.)),
+            border: UiRect::all(Val::Px(1.)),
+            border_radius: BorderRadius::all(Val::Px(5.)),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        TextInput {
+            placeholder: placeholder.to_string(),
+            ..default()
+        },
+        bundle,
+        Name::new(name.to_string()),
+        children![(
+            Text::new(placeholder),
+            text_style(ThemeColor::MutedText),
+            TextLayout::no_wrap(),
+            Label,
+            TextInputTextMarker,
+        )],
+    )
+}
+
+/// Whether a toggle chip is switched on, which fills it in the theme's colours.
+#[derive(Component)]
+pub(crate) struct ToggleChip {
+    pub(crate) active: bool,
+}
+
+/// A rounded button with a text label that is switched on and off.
+pub(crate) fn toggle_chip<B: Bundle>(
+    bundle: B,
+    label: String,
+    active: bool,
+    name: &str,
+) -> impl Bundle {
+    (
+        Button,
+        Node {
+            border: UiRect::all(Val::Px(1.)),
+            padding: UiRect::horizontal(Val::Px(5.)),
+            min_height: Val::Px(20.),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            align_self: AlignSelf::Center,
+            margin: UiRect::all(Val::Px(1.)),
+            border_radius: BorderRadius::all(Val::Px(20.)),
+            ..default()
+        },
+        ToggleChip { active },
+        bundle,
+        Name::new(name.to_string()),
+        children![(Text::new(label), text_style(ThemeColor::Text), Label)],
+    )
+}
@@ -0,0 +1,80 @@
+//! Zooming the log lines with a pinch or Ctrl+scroll.
+
+use crate::{
+    log_viewer::{ListContainerMarker, LogViewerState},
+    LogFontSize,
+};
+use bevy_ecs::prelude::*;
+use bevy_input::{gestures::PinchGesture, touch::Touches};
+use bevy_picking::{hover::HoverMap, pointer::PointerId};
+
+/// Smallest size in logical px the log lines can be zoomed to.
+const MIN_LOG_FONT_SIZE: f32 = 4.;
+
+/// Largest size in logical px the log lines can be zoomed to.
+const MAX_LOG_FONT_SIZE: f32 = 48.;
+
+/// Factor the log lines grow by per line scrolled with Ctrl held.
+pub(crate) const SCROLL_ZOOM_STEP: f32 = 1.1;
+
+/// Scales the size of the log lines by `factor`, within limits.
+pub(crate) fn zoom(font_size: &mut ResMut<LogFontSize>, factor: f32) {
+    let size = (font_size.0 * factor).clamp(MIN_LOG_FONT_SIZE, MAX_LOG_FONT_SIZE);
+    // Every change restyles all log lines, so skip the ones that don't do anything.
+    if size != font_size.0 {
+        font_size.0 = size;
+    }
+}
+
+/// Whether the pointer is over the log list.
+pub(crate) fn hovers_list(
+    hover_map: &HoverMap,
+    pointer: &PointerId,
+    container_query: &Query<(), With<ListContainerMarker>>,
+) -> bool {
+    hover_map
+        .get(pointer)
+        .is_some_and(|hits| hits.keys().any(|entity| container_query.contains(*entity)))
+}
+
+/// Zooms with a trackpad pinch over the list, or with two fingers pinching on it.
+pub(crate) fn pinch_zoom(
+    mut pinch_events: MessageReader<PinchGesture>,
+    touches: Option<Res<Touches>>,
+    hover_map: Res<HoverMap>,
+    container_query: Query<(), With<ListContainerMarker>>,
+    log_viewer_res: Res<LogViewerState>,
+    mut font_size: ResMut<LogFontSize>,
+) {
+    if !log_viewer_res.visible {
+        pinch_events.clear();
+        return;
+    }
+
+    for PinchGesture(delta) in pinch_events.read() {
+        if hovers_list(&hover_map, &PointerId::Mouse, &container_query) {
+            zoom(&mut font_size, 1. + delta);
+        }
+    }
+
+    let Some(touches) = touches else {
+        return;
+    };
+    let mut pressed = touches.iter();
+    let (Some(first), Some(second), None) = (pressed.next(), pressed.next(), pressed.next()) else {
+        return;
+    };
+    if ![first, second]
+        .iter()
+        .all(|touch| hovers_list(&hover_map, &PointerId::Touch(touch.id()), &container_query))
+    {
+        return;
+    }
+    let distance = first.position().distance(second.position());
+    let previous = first
+        .previous_position()
+        .distance(second.previous_position());
+    if previous > 0. && distance != previous {
+        zoom(&mut font_size, distance / previous);
+    }
+}